@@ -1,4 +1,13 @@
-const COMMANDS: &[&str] = &["show_snap_overlay"];
+const COMMANDS: &[&str] = &[
+    "show_snap_overlay",
+    "titlebar_button_click",
+    "control_action",
+    "sync_controls",
+    "report_metrics",
+    "titlebar_mounted",
+    "controls_layout",
+    "window_state",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
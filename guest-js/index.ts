@@ -1,5 +1,64 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+
+export type Control = "minimize" | "maximize" | "close";
+
+/** Which window controls the window has and where they go, as resolved for the platform. */
+export interface ControlsLayout {
+	/** The style the controls are drawn in. */
+	style: "windows" | "gnome" | "mac";
+	/** The OS the app runs on, e.g. `windows`, `linux` or `macos`. */
+	platform: string;
+	/** The end of the titlebar the controls go at. */
+	side: "left" | "right";
+	/** The controls to show, in order. */
+	controls: Control[];
+	/** The controls that are shown but do nothing when pressed. */
+	disabled: Control[];
+}
+
+export interface WindowState {
+	maximized: boolean;
+	minimized: boolean;
+	fullscreen: boolean;
+	focused: boolean;
+}
 
 export async function show_snap_overlay() {
 	await invoke("plugin:decorum|show_snap_overlay");
 }
+
+/** The controls to render in headless mode, or `null` when the window keeps its native controls. */
+export async function controls_layout(): Promise<ControlsLayout | null> {
+	return await invoke("plugin:decorum|controls_layout");
+}
+
+/** Runs `handler` when the controls to render change, e.g. after `set_control_state`. */
+export async function on_controls_layout(
+	handler: (layout: ControlsLayout) => void,
+): Promise<UnlistenFn> {
+	return await getCurrentWebviewWindow().listen<ControlsLayout>(
+		"decorum://controls-layout",
+		(event) => handler(event.payload),
+	);
+}
+
+export async function window_state(): Promise<WindowState> {
+	return await invoke("plugin:decorum|window_state");
+}
+
+/** Runs `handler` whenever the window is maximized, minimized, made fullscreen or focused. */
+export async function on_window_state(
+	handler: (state: WindowState) => void,
+): Promise<UnlistenFn> {
+	return await getCurrentWebviewWindow().listen<WindowState>(
+		"decorum://window-state",
+		(event) => handler(event.payload),
+	);
+}
+
+/** Press one of the window controls, going through `on_control_action` on the rust side. */
+export async function control_action(control: Control) {
+	await invoke("plugin:decorum|control_action", { control });
+}
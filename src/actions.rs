@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, Runtime, WebviewWindow};
+
+use crate::control_state::{self, ControlState};
+
+/// A window control button pressed on the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlAction {
+    Minimize,
+    Maximize,
+    /// The maximize button, pressed while the window is maximized.
+    Unmaximize,
+    Close,
+}
+
+/// What to do with a control action, returned by the handler set with
+/// [`Builder::on_control_action`](crate::Builder::on_control_action).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionDecision {
+    /// Minimize, maximize or close the window as usual.
+    #[default]
+    Proceed,
+    /// Leave the window as it is, e.g. after hiding it to the tray instead.
+    Cancel,
+}
+
+/// A window control button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Control {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+impl Control {
+    /// All the controls, in their default order.
+    pub(crate) const ALL: [Control; 3] = [Control::Minimize, Control::Maximize, Control::Close];
+
+    /// Id of the control, as used for its part, e.g. `decorum-controls::part(<id>)`.
+    pub(crate) fn id(self) -> &'static str {
+        match self {
+            Control::Minimize => "minimize",
+            Control::Maximize => "maximize",
+            Control::Close => "close",
+        }
+    }
+}
+
+pub(crate) type ActionHandler<R> =
+    Box<dyn Fn(&WebviewWindow<R>, ControlAction) -> ActionDecision + Send + Sync>;
+
+/// The handler for control actions set on the plugin builder.
+pub struct ControlActions<R: Runtime> {
+    pub(crate) handler: Option<ActionHandler<R>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ControlActionPayload {
+    action: ControlAction,
+    decision: ActionDecision,
+}
+
+/// Let the app decide on a control action, then carry it out.
+/// The action and the decision are emitted to the window as a `decorum://control-action` event.
+pub fn run<R: Runtime>(win: &WebviewWindow<R>, control: Control) {
+    // The page may not be up to date with a control that was just disabled
+    if control_state::get(win, control) != ControlState::Enabled {
+        control_state::sync(win);
+        return;
+    }
+
+    let action = match control {
+        Control::Minimize => ControlAction::Minimize,
+        Control::Maximize if win.is_maximized().unwrap_or(false) => ControlAction::Unmaximize,
+        Control::Maximize => ControlAction::Maximize,
+        Control::Close => ControlAction::Close,
+    };
+
+    let decision = win
+        .try_state::<ControlActions<R>>()
+        .and_then(|actions| actions.handler.as_ref().map(|handler| handler(win, action)))
+        .unwrap_or_default();
+
+    win.emit_to(
+        win.label(),
+        "decorum://control-action",
+        ControlActionPayload { action, decision },
+    )
+    .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+
+    if decision == ActionDecision::Cancel {
+        return;
+    }
+
+    let result = match action {
+        ControlAction::Minimize => win.minimize(),
+        ControlAction::Maximize => win.maximize(),
+        ControlAction::Unmaximize => win.unmaximize(),
+        ControlAction::Close => win.close(),
+    };
+
+    result.unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Error, Manager, Runtime, WebviewWindow};
+
+use crate::icons::Icon;
+
+/// Where a custom button is placed, relative to the window controls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ButtonPosition {
+    /// Before the window controls.
+    #[default]
+    Start,
+    /// After the window controls.
+    End,
+}
+
+/// A custom button drawn next to the window controls, with the same styling and spacing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitlebarButton {
+    /// Id of the button, used for its part `decorum-controls::part(<id>)` and in click events.
+    pub id: String,
+    pub icon: Icon,
+    /// Label of the button, shown as a tooltip and read by screen readers.
+    pub tooltip: Option<String>,
+    pub position: ButtonPosition,
+}
+
+type OnClick<R> = Arc<dyn Fn(&WebviewWindow<R>) + Send + Sync>;
+type WindowButtons<R> = Vec<(TitlebarButton, OnClick<R>)>;
+
+/// The custom buttons of each window and their click handlers, keyed by window label.
+pub struct Buttons<R: Runtime> {
+    windows: Mutex<HashMap<String, WindowButtons<R>>>,
+}
+
+impl<R: Runtime> Default for Buttons<R> {
+    fn default() -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// What the window controls script needs to render a custom button.
+#[derive(Debug, Serialize)]
+pub struct ButtonConfig {
+    id: String,
+    tooltip: Option<String>,
+    position: ButtonPosition,
+}
+
+/// Ids of the window controls, which custom buttons can't take.
+const RESERVED_IDS: [&str; 4] = ["close", "maximize", "minimize", "restore"];
+
+/// Add a custom button to the window, replacing any button with the same id.
+/// Fails when the id is taken by a window control or isn't a single word.
+pub fn add<R: Runtime, F>(
+    win: &WebviewWindow<R>,
+    button: TitlebarButton,
+    on_click: F,
+) -> Result<(), Error>
+where
+    F: Fn(&WebviewWindow<R>) + Send + Sync + 'static,
+{
+    if RESERVED_IDS.contains(&button.id.as_str()) {
+        return Err(anyhow::anyhow!(
+            "titlebar button id `{}` is taken by a window control",
+            button.id
+        )
+        .into());
+    }
+
+    if button.id.is_empty() || button.id.contains(char::is_whitespace) {
+        return Err(
+            anyhow::anyhow!("titlebar button id `{}` must be a single word", button.id).into(),
+        );
+    }
+
+    let Some(buttons) = win.try_state::<Buttons<R>>() else {
+        return Ok(());
+    };
+
+    let mut windows = buttons.windows.lock().unwrap();
+    let window = windows.entry(win.label().to_string()).or_default();
+
+    window.retain(|(b, _)| b.id != button.id);
+    window.push((button, Arc::new(on_click)));
+
+    Ok(())
+}
+
+/// Forget about the buttons of a window once it's destroyed.
+pub fn remove_all<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    if let Some(buttons) = app.try_state::<Buttons<R>>() {
+        buttons.windows.lock().unwrap().remove(label);
+    }
+}
+
+/// The custom buttons of the window and their icons, for the window controls script.
+pub fn config<R: Runtime>(win: &WebviewWindow<R>) -> (Vec<ButtonConfig>, Vec<(String, Icon)>) {
+    let Some(buttons) = win.try_state::<Buttons<R>>() else {
+        return Default::default();
+    };
+
+    let windows = buttons.windows.lock().unwrap();
+    let Some(window) = windows.get(win.label()) else {
+        return Default::default();
+    };
+
+    window
+        .iter()
+        .map(|(button, _)| {
+            let config = ButtonConfig {
+                id: button.id.clone(),
+                tooltip: button.tooltip.clone(),
+                position: button.position,
+            };
+
+            (config, (button.id.clone(), button.icon.clone()))
+        })
+        .unzip()
+}
+
+/// Run the click handler of a custom button and let the page know it was clicked.
+pub fn click<R: Runtime>(win: &WebviewWindow<R>, id: &str) {
+    let on_click = win.try_state::<Buttons<R>>().and_then(|buttons| {
+        let windows = buttons.windows.lock().unwrap();
+        windows
+            .get(win.label())?
+            .iter()
+            .find(|(button, _)| button.id == id)
+            .map(|(_, on_click)| on_click.clone())
+    });
+
+    // The handler may add or remove buttons, so it runs without holding the lock
+    if let Some(on_click) = on_click {
+        on_click(win);
+    }
+
+    win.emit_to(win.label(), "decorum://titlebar-button", id)
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
@@ -1,4 +1,9 @@
-use tauri;
+use tauri::{Runtime, WebviewWindow};
+
+use crate::actions::Control;
+use crate::controls::ControlsLayout;
+use crate::metrics::TitlebarMetrics;
+use crate::window_state::WindowState;
 
 #[tauri::command]
 pub async fn show_snap_overlay() {
@@ -19,3 +24,42 @@ pub async fn show_snap_overlay() {
         enigo.key_click(Key::Alt);
     }
 }
+
+#[tauri::command]
+pub fn titlebar_button_click<R: Runtime>(window: WebviewWindow<R>, id: String) {
+    crate::buttons::click(&window, &id);
+}
+
+#[tauri::command]
+pub async fn control_action<R: Runtime>(window: WebviewWindow<R>, control: Control) {
+    crate::actions::run(&window, control);
+}
+
+#[tauri::command]
+pub async fn sync_controls<R: Runtime>(window: WebviewWindow<R>) {
+    crate::control_state::sync(&window);
+}
+
+#[tauri::command]
+pub async fn report_metrics<R: Runtime>(window: WebviewWindow<R>, metrics: TitlebarMetrics) {
+    crate::metrics::report(&window, metrics);
+}
+
+#[tauri::command]
+pub async fn titlebar_mounted<R: Runtime>(
+    window: WebviewWindow<R>,
+    part: String,
+    error: Option<String>,
+) {
+    crate::ready::report(&window, &part, error);
+}
+
+#[tauri::command]
+pub async fn controls_layout<R: Runtime>(window: WebviewWindow<R>) -> Option<ControlsLayout> {
+    crate::controls::resolve_layout(&window)
+}
+
+#[tauri::command]
+pub async fn window_state<R: Runtime>(window: WebviewWindow<R>) -> WindowState {
+    crate::window_state::get(&window)
+}
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+
+use crate::icons::IconSet;
+
+/// Look of the window controls drawn by decorum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ControlStyle {
+    /// The look of the current platform. On macOS the native traffic lights are kept.
+    #[default]
+    Native,
+    /// Fluent-style controls, as on Windows 11.
+    Windows,
+    /// Adwaita-style controls, as on GNOME.
+    Gnome,
+    /// Traffic lights drawn in HTML, as on macOS.
+    MacLike,
+}
+
+impl ControlStyle {
+    /// The style the controls are drawn in on this platform,
+    /// or `None` when the native macOS controls are used instead.
+    pub(crate) fn rendered(self) -> Option<ControlStyle> {
+        match self {
+            #[cfg(target_os = "windows")]
+            ControlStyle::Native => Some(ControlStyle::Windows),
+            #[cfg(target_os = "macos")]
+            ControlStyle::Native => None,
+            #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+            ControlStyle::Native => Some(ControlStyle::Gnome),
+            style => Some(style),
+        }
+    }
+}
+
+/// Plugin configuration, read from `plugins > decorum` in `tauri.conf.json`
+/// and overridden by the options set on the plugin [`Builder`](crate::Builder).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    #[serde(default)]
+    pub control_style: ControlStyle,
+    #[serde(default)]
+    pub show_when_ready: bool,
+    /// Leave the window controls to the page, which gets their layout and the window state.
+    #[serde(default)]
+    pub headless: bool,
+    /// CSS selector of the element the window controls are mounted into.
+    #[serde(default)]
+    pub controls_mount: Option<String>,
+    #[serde(skip)]
+    pub icons: IconSet,
+}
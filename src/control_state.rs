@@ -0,0 +1,79 @@
+use serde::Serialize;
+use tauri::{Runtime, WebviewWindow};
+
+use crate::actions::Control;
+use crate::windows;
+
+/// Whether a window control is shown and can be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlState {
+    Enabled,
+    /// Shown greyed out, and does nothing when pressed.
+    Disabled,
+    Hidden,
+}
+
+/// The state of a control from the window flags, e.g. hidden when the window isn't minimizable.
+fn from_flags<R: Runtime>(win: &WebviewWindow<R>, control: Control) -> ControlState {
+    let enabled = match control {
+        Control::Minimize => win.is_minimizable().unwrap_or(false),
+        Control::Maximize => {
+            win.is_maximizable().unwrap_or(false) && win.is_resizable().unwrap_or(false)
+        }
+        Control::Close => win.is_closable().unwrap_or(false),
+    };
+
+    match enabled {
+        true => ControlState::Enabled,
+        false => ControlState::Hidden,
+    }
+}
+
+/// The state of a control, as set on the window or else from the window flags.
+pub fn get<R: Runtime>(win: &WebviewWindow<R>, control: Control) -> ControlState {
+    let state = windows::get(win, win.label(), |window| {
+        window.controls.get(&control).copied()
+    })
+    .flatten();
+
+    state.unwrap_or_else(|| from_flags(win, control))
+}
+
+/// The state of all controls, in their default order.
+fn resolve<R: Runtime>(win: &WebviewWindow<R>) -> Vec<(Control, ControlState)> {
+    Control::ALL
+        .iter()
+        .map(|control| (*control, get(win, *control)))
+        .collect()
+}
+
+/// The state of all controls, remembered as the ones on the page.
+pub fn render<R: Runtime>(win: &WebviewWindow<R>) -> Vec<(Control, ControlState)> {
+    let controls = resolve(win);
+
+    windows::update(win, win.label(), |window| {
+        window.rendered = Some(controls.clone())
+    });
+
+    controls
+}
+
+/// Set the state of a control and update the page.
+pub fn set<R: Runtime>(win: &WebviewWindow<R>, control: Control, state: ControlState) {
+    windows::update(win, win.label(), |window| {
+        window.controls.insert(control, state)
+    });
+
+    sync(win);
+}
+
+/// Render the controls again if their state changed since they were last rendered,
+/// e.g. after `set_maximizable(false)`.
+pub fn sync<R: Runtime>(win: &WebviewWindow<R>) {
+    let rendered = windows::get(win, win.label(), |window| window.rendered.clone()).flatten();
+
+    if rendered.is_some_and(|rendered| rendered != resolve(win)) {
+        crate::controls::refresh(win);
+    }
+}
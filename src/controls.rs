@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::{Emitter, Manager, Runtime, WebviewWindow};
+
+use crate::buttons::{self, ButtonConfig};
+use crate::config::{Config, ControlStyle};
+use crate::control_state::{self, ControlState};
+use crate::icons;
+
+/// Which window controls a window has and where they go, as resolved for the platform.
+/// In headless mode, the page gets this to render the controls itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ControlsLayout {
+    /// The style the controls are drawn in: `windows`, `gnome` or `mac`.
+    pub style: String,
+    /// The OS the app runs on, e.g. `windows`, `linux` or `macos`.
+    pub platform: String,
+    /// The end of the titlebar the controls go at, `left` or `right`.
+    pub side: String,
+    /// The controls to show, in order, e.g. `minimize`, `maximize` and `close`.
+    pub controls: Vec<String>,
+    /// The controls that are shown but do nothing when pressed.
+    pub disabled: Vec<String>,
+}
+
+/// What the window controls script needs to render the controls of a window.
+/// The style picks the default metrics and palettes, the platform the native behaviors.
+#[derive(Debug, Serialize)]
+struct ControlsConfig {
+    #[serde(flatten)]
+    layout: ControlsLayout,
+    buttons: Vec<ButtonConfig>,
+    icons: HashMap<String, String>,
+    mount: Option<String>,
+}
+
+/// Glyphs from the Segoe Fluent Icons / Segoe MDL2 Assets fonts, when one of them is installed
+#[cfg(target_os = "windows")]
+fn native_icons(icons: &mut HashMap<String, String>) {
+    let fonts = std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let installed = ["SegoeIcons.ttf", "segmdl2.ttf"].iter().any(|font| {
+        std::path::Path::new(&fonts)
+            .join("Fonts")
+            .join(font)
+            .exists()
+    });
+
+    if !installed {
+        return;
+    }
+
+    let glyphs = [
+        ("minimize", "\u{E921}"),
+        ("maximize", "\u{E922}"),
+        ("restore", "\u{E923}"),
+        ("close", "\u{E8BB}"),
+    ];
+
+    for (id, glyph) in glyphs {
+        icons.insert(id.to_string(), glyph.to_string());
+    }
+}
+
+/// Symbolic icons from the system icon theme
+#[cfg(target_os = "linux")]
+fn native_icons(icons: &mut HashMap<String, String>) {
+    use linicon::{lookup_icon, IconType};
+
+    for control in ["minimize", "maximize", "restore", "close"] {
+        let icon = lookup_icon(format!("window-{}-symbolic", control))
+            .flatten()
+            .find(|icon| icon.icon_type == IconType::SVG);
+
+        if let Some(Ok(icon_data)) = icon.map(|icon| std::fs::read_to_string(icon.path)) {
+            icons.insert(control.to_string(), icon_data);
+        }
+    }
+}
+
+/// Follow the order and side of the GNOME button layout.
+#[cfg(target_os = "linux")]
+fn native_order(controls: &mut Vec<String>, side: &mut &'static str) {
+    if let Ok(layout) = crate::dconf::read("/org/gnome/desktop/wm/preferences/button-layout") {
+        (*controls, *side) = parse_button_layout(&layout, controls);
+    }
+}
+
+/// The controls in the order of a GNOME button layout, e.g. 'appmenu:minimize,maximize,close'
+/// or 'close,minimize,maximize:appmenu' for the controls on the left, and the side they go on.
+/// The controls are drawn as one group, so split layouts like 'close:minimize,maximize'
+/// keep their order and go at the side of the close button.
+/// Layouts without any of the controls leave them as they are, on the right.
+#[cfg(any(target_os = "linux", test))]
+fn parse_button_layout(layout: &str, controls: &[String]) -> (Vec<String>, &'static str) {
+    let (left, right) = layout.split_once(':').unwrap_or(("", layout));
+
+    let pick = |buttons: &str| {
+        buttons
+            .split(',')
+            .filter(|control| controls.iter().any(|c| c == control))
+            .map(|control| control.to_string())
+            .collect::<Vec<String>>()
+    };
+
+    let (left, right) = (pick(left), pick(right));
+
+    if left.is_empty() && right.is_empty() {
+        return (controls.to_vec(), "right");
+    }
+
+    let side = if right.is_empty() || left.iter().any(|c| c == "close") {
+        "left"
+    } else {
+        "right"
+    };
+
+    (left.into_iter().chain(right).collect(), side)
+}
+
+/// The controls of the window in the given style, adding the platform's icons for them.
+fn layout<R: Runtime>(
+    win: &WebviewWindow<R>,
+    style: ControlStyle,
+    icons: &mut HashMap<String, String>,
+) -> ControlsLayout {
+    let states = control_state::render(win);
+
+    let mut controls = states
+        .iter()
+        .filter(|(_, state)| *state != ControlState::Hidden)
+        .map(|(control, _)| control.id().to_string())
+        .collect::<Vec<String>>();
+
+    let disabled = states
+        .iter()
+        .filter(|(_, state)| *state == ControlState::Disabled)
+        .map(|(control, _)| control.id().to_string())
+        .collect();
+
+    let mut side = "right";
+
+    // Only the Windows and GNOME styles have platform icons
+    #[cfg(target_os = "macos")]
+    let _ = icons;
+
+    let style = match style {
+        ControlStyle::Windows => {
+            #[cfg(target_os = "windows")]
+            native_icons(icons);
+
+            "windows"
+        }
+        ControlStyle::MacLike => {
+            // Close, minimize and maximize from left to right, as on macOS
+            controls.sort_by_key(|control| match control.as_str() {
+                "close" => 0,
+                "minimize" => 1,
+                _ => 2,
+            });
+            side = "left";
+
+            "mac"
+        }
+        ControlStyle::Gnome | ControlStyle::Native => {
+            #[cfg(target_os = "linux")]
+            {
+                native_order(&mut controls, &mut side);
+                native_icons(icons);
+            }
+
+            "gnome"
+        }
+    };
+
+    ControlsLayout {
+        style: style.to_string(),
+        platform: std::env::consts::OS.to_string(),
+        side: side.to_string(),
+        controls,
+        disabled,
+    }
+}
+
+fn config<R: Runtime>(win: &WebviewWindow<R>, style: ControlStyle) -> ControlsConfig {
+    let mut icons = icons::builtin(style);
+    let layout = layout(win, style, &mut icons);
+
+    let (buttons, button_icons) = buttons::config(win);
+    for (id, icon) in button_icons {
+        if let Some(svg) = icon.load(win.app_handle()) {
+            icons.insert(id, svg);
+        }
+    }
+
+    // Icons set from Rust take precedence over the platform's
+    if let Some(config) = win.try_state::<Config>() {
+        icons.extend(config.icons.load(win.app_handle()));
+    }
+
+    ControlsConfig {
+        layout,
+        buttons,
+        icons,
+        mount: mount(win),
+    }
+}
+
+/// The control style set in the plugin config.
+pub fn style<R: Runtime, M: Manager<R>>(manager: &M) -> ControlStyle {
+    manager
+        .try_state::<Config>()
+        .map(|config| config.control_style)
+        .unwrap_or_default()
+}
+
+/// Whether the page renders the controls itself, set in the plugin config.
+pub fn headless<R: Runtime, M: Manager<R>>(manager: &M) -> bool {
+    manager
+        .try_state::<Config>()
+        .is_some_and(|config| config.headless)
+}
+
+/// The selector of the element the controls are mounted into, set in the plugin config.
+fn mount<R: Runtime>(win: &WebviewWindow<R>) -> Option<String> {
+    win.try_state::<Config>()
+        .and_then(|config| config.controls_mount.clone())
+}
+
+/// The titlebar script, which leaves the titlebar out when the controls have a place of their own
+/// or the page renders them itself.
+pub fn titlebar_script<R: Runtime>(win: &WebviewWindow<R>) -> String {
+    let options = serde_json::json!({
+        "mount": mount(win),
+        "headless": headless(win),
+    });
+
+    include_str!("js/titlebar.js").replacen("\"@decorum-titlebar\"", &options.to_string(), 1)
+}
+
+/// The controls the page should render in headless mode,
+/// or `None` when the window keeps its native controls.
+pub fn resolve_layout<R: Runtime>(win: &WebviewWindow<R>) -> Option<ControlsLayout> {
+    let style = style(win).rendered()?;
+
+    Some(layout(win, style, &mut HashMap::new()))
+}
+
+/// The window controls script, with the controls and icons for the window,
+/// or `None` when the window keeps its native controls or the page renders them itself.
+pub fn script<R: Runtime>(win: &WebviewWindow<R>) -> Option<String> {
+    if headless(win) {
+        return None;
+    }
+
+    let style = style(win).rendered()?;
+    let config = serde_json::to_string(&config(win, style)).unwrap_or_default();
+
+    Some(include_str!("js/controls.js").replacen("\"@decorum-config\"", &config, 1))
+}
+
+/// Render the window controls again, e.g. after buttons are added.
+/// In headless mode the new layout is emitted as a `decorum://controls-layout` event instead.
+/// Windows without an overlay titlebar are left alone.
+pub fn refresh<R: Runtime>(win: &WebviewWindow<R>) {
+    if !crate::windows::is_decorated(win, win.label()) {
+        return;
+    }
+
+    if headless(win) {
+        if let Some(layout) = resolve_layout(win) {
+            win.emit_to(win.label(), "decorum://controls-layout", layout)
+                .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+        }
+        return;
+    }
+
+    if let Some(script) = script(win) {
+        win.eval(script)
+            .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controls(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn follows_the_button_layout() {
+        let all = controls(&["minimize", "maximize", "close"]);
+
+        assert_eq!(
+            parse_button_layout("appmenu:minimize,maximize,close", &all),
+            (controls(&["minimize", "maximize", "close"]), "right")
+        );
+        assert_eq!(
+            parse_button_layout("close,minimize,maximize:", &all),
+            (controls(&["close", "minimize", "maximize"]), "left")
+        );
+        assert_eq!(
+            parse_button_layout("close,maximize:appmenu", &controls(&["close"])),
+            (controls(&["close"]), "left")
+        );
+    }
+
+    #[test]
+    fn keeps_split_layouts_together() {
+        let all = controls(&["minimize", "maximize", "close"]);
+
+        assert_eq!(
+            parse_button_layout("close:minimize,maximize", &all),
+            (controls(&["close", "minimize", "maximize"]), "left")
+        );
+        assert_eq!(
+            parse_button_layout("minimize:close", &all),
+            (controls(&["minimize", "close"]), "right")
+        );
+    }
+
+    #[test]
+    fn ignores_layouts_without_the_controls() {
+        let all = controls(&["minimize", "maximize", "close"]);
+
+        assert_eq!(parse_button_layout("", &all), (all.clone(), "right"));
+        assert_eq!(parse_button_layout(":", &all), (all.clone(), "right"));
+        assert_eq!(
+            parse_button_layout("appmenu:spacer", &all),
+            (all.clone(), "right")
+        );
+        assert_eq!(
+            parse_button_layout("garbage,,::", &all),
+            (all.clone(), "right")
+        );
+    }
+}
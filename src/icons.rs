@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Runtime};
+
+use crate::config::ControlStyle;
+
+/// An SVG icon for a window control.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Icon {
+    /// The SVG markup of the icon, e.g. from `include_str!`.
+    Svg(String),
+    /// Path of an SVG file bundled with the frontend, e.g. `icons/close.svg`.
+    ///
+    /// Assets are only bundled in release builds: under `tauri dev` with a `devUrl` the frontend
+    /// is served by the dev server, so these icons fall back as if they were unset.
+    /// Use [`Icon::Svg`] with `include_str!` for icons that should show up in development too.
+    Asset(String),
+}
+
+impl Icon {
+    pub(crate) fn load<R: Runtime>(&self, app: &AppHandle<R>) -> Option<String> {
+        match self {
+            Icon::Svg(svg) => Some(svg.clone()),
+            Icon::Asset(path) => app
+                .asset_resolver()
+                .get(path.clone())
+                .and_then(|asset| String::from_utf8(asset.bytes).ok()),
+        }
+    }
+}
+
+/// Icons of the window controls, used in place of the platform's on every OS.
+///
+/// Icons left unset fall back to the platform's, and then to the SVG icons shipped with the crate.
+/// Custom buttons get their icon from here by id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IconSet {
+    minimize: Option<Icon>,
+    maximize: Option<Icon>,
+    restore: Option<Icon>,
+    close: Option<Icon>,
+    custom: HashMap<String, Icon>,
+}
+
+impl IconSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn minimize(mut self, icon: Icon) -> Self {
+        self.minimize = Some(icon);
+        self
+    }
+
+    pub fn maximize(mut self, icon: Icon) -> Self {
+        self.maximize = Some(icon);
+        self
+    }
+
+    /// Icon of the maximize button while the window is maximized.
+    pub fn restore(mut self, icon: Icon) -> Self {
+        self.restore = Some(icon);
+        self
+    }
+
+    pub fn close(mut self, icon: Icon) -> Self {
+        self.close = Some(icon);
+        self
+    }
+
+    /// Icon of a custom titlebar button.
+    pub fn custom(mut self, id: impl Into<String>, icon: Icon) -> Self {
+        self.custom.insert(id.into(), icon);
+        self
+    }
+
+    /// The SVG markup of the icons that are set, keyed by control id.
+    pub(crate) fn load<R: Runtime>(&self, app: &AppHandle<R>) -> HashMap<String, String> {
+        let controls = [
+            ("minimize", &self.minimize),
+            ("maximize", &self.maximize),
+            ("restore", &self.restore),
+            ("close", &self.close),
+        ];
+
+        let controls = controls
+            .into_iter()
+            .filter_map(|(id, icon)| Some((id.to_string(), icon.as_ref()?)));
+
+        controls
+            .chain(self.custom.iter().map(|(id, icon)| (id.clone(), icon)))
+            .filter_map(|(id, icon)| match icon.load(app) {
+                Some(svg) => Some((id, svg)),
+                None => {
+                    println!("decorum error: could not load the icon for {}", id);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// SVG icons shipped with the crate for each style.
+pub fn builtin(style: ControlStyle) -> HashMap<String, String> {
+    let icons = match style {
+        ControlStyle::Windows => [
+            ("minimize", include_str!("icons/windows/minimize.svg")),
+            ("maximize", include_str!("icons/windows/maximize.svg")),
+            ("restore", include_str!("icons/windows/restore.svg")),
+            ("close", include_str!("icons/windows/close.svg")),
+        ],
+        ControlStyle::MacLike => [
+            ("minimize", include_str!("icons/mac/minimize.svg")),
+            ("maximize", include_str!("icons/mac/maximize.svg")),
+            ("restore", include_str!("icons/mac/restore.svg")),
+            ("close", include_str!("icons/mac/close.svg")),
+        ],
+        ControlStyle::Gnome | ControlStyle::Native => [
+            ("minimize", include_str!("icons/gnome/minimize.svg")),
+            ("maximize", include_str!("icons/gnome/maximize.svg")),
+            ("restore", include_str!("icons/gnome/restore.svg")),
+            ("close", include_str!("icons/gnome/close.svg")),
+        ],
+    };
+
+    icons
+        .iter()
+        .map(|(id, icon)| (id.to_string(), icon.to_string()))
+        .collect()
+}
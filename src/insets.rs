@@ -0,0 +1,55 @@
+use serde::Serialize;
+use tauri::{Runtime, WebviewWindow};
+
+/// Space taken by the native window controls, in logical pixels.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+struct NativeInsets {
+    left: f64,
+    right: f64,
+    height: f64,
+}
+
+/// The traffic lights, unless they are hidden for custom controls or the window is fullscreen.
+/// Needs to run on the main thread.
+#[cfg(target_os = "macos")]
+fn native_insets<R: Runtime>(win: &WebviewWindow<R>) -> NativeInsets {
+    if win.is_fullscreen().unwrap_or(false) {
+        return NativeInsets::default();
+    }
+
+    let geometry = win.ns_window().ok().and_then(|ns_window| {
+        crate::traffic::traffic_lights_geometry(crate::traffic::UnsafeWindowHandle(ns_window))
+    });
+
+    match geometry {
+        Some((left, height)) => NativeInsets {
+            left,
+            right: 0.0,
+            height,
+        },
+        None => NativeInsets::default(),
+    }
+}
+
+fn eval<R: Runtime>(win: &WebviewWindow<R>, native: NativeInsets) {
+    let native = serde_json::to_string(&native).unwrap_or_default();
+    let script = include_str!("js/insets.js").replacen("\"@decorum-native-insets\"", &native, 1);
+
+    win.eval(script)
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
+
+/// Publish the space taken by the window controls into the page as CSS variables,
+/// e.g. after a page load or when the traffic lights move.
+/// The page keeps them updated as the titlebar and controls change size.
+pub fn publish<R: Runtime>(win: &WebviewWindow<R>) {
+    #[cfg(target_os = "macos")]
+    {
+        let win2 = win.clone();
+        win.run_on_main_thread(move || eval(&win2, native_insets(&win2)))
+            .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    eval(win, NativeInsets::default());
+}
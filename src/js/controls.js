@@ -1,25 +1,4 @@
-function waitForElm(selector) {
-	return new Promise((resolve) => {
-		if (document.querySelector(selector)) {
-			return resolve(document.querySelector(selector));
-		}
-
-		const observer = new MutationObserver((mutations) => {
-			if (document.querySelector(selector)) {
-				observer.disconnect();
-				resolve(document.querySelector(selector));
-			}
-		});
-
-		// If you get "parameter 1 is not of type 'Node'" error, see https://stackoverflow.com/a/77855838/492336
-		observer.observe(document.body, {
-			childList: true,
-			subtree: true,
-		});
-	});
-}
-
-document.addEventListener("DOMContentLoaded", () => {
+(() => {
 	const tauri = window.__TAURI__;
 
 	if (!tauri) {
@@ -30,154 +9,140 @@ document.addEventListener("DOMContentLoaded", () => {
 		return;
 	}
 
-	const win = tauri.window.getCurrentWindow();
 	const invoke = tauri.core.invoke;
 
-	console.log("DECORUM: Waiting for [data-tauri-decorum-tb] ...");
+	// Before eval-ing, the line below is replaced from the rust side with the style, the platform,
+	// the controls enabled on the window, the custom buttons and their icons
+	let config = "@decorum-config";
+
+	// Evaluated again when the controls change, the first run keeps them on the page
+	if (window.__DECORUM_CONTROLS__) {
+		window.__DECORUM_CONTROLS__.update(config);
+		return;
+	}
+
+	const mounted = (error) => {
+		invoke("plugin:decorum|titlebar_mounted", { part: "controls", error }).catch(() => {});
+	};
+
+	// <decorum-controls> is registered on every page by the plugin, see elements.js
+	if (!customElements.get("decorum-controls")) {
+		mounted("<decorum-controls> is not defined on this page");
+		return;
+	}
 
-	// Add debounce function
-	const debounce = (func, delay) => {
-		let timeoutId;
-		return (...args) => {
-			clearTimeout(timeoutId);
-			timeoutId = setTimeout(() => func(...args), delay);
-		};
+	const build = () => {
+		const actions = document.createElement("decorum-controls");
+		actions.config = config;
+		return actions;
 	};
 
-	// Debounce the control creation
-	const debouncedCreateControls = debounce(() => {
-		const tbEl = document.querySelector("[data-tauri-decorum-tb]");
-		if (!tbEl) return;
+	const setAttributes = () => {
+		document.documentElement.setAttribute("data-decorum-style", config.style);
+		document.documentElement.setAttribute("data-decorum-platform", config.platform);
+	};
 
-		// Check if controls already exist
-		if (tbEl.querySelector(".decorum-tb-btn")) {
-			console.log("DECORUM: Controls already exist. Skipping creation.");
-			return;
+	// The controls on the page, kept in the first titlebar as it's replaced, moved or duplicated
+	let actions = null;
+
+	let reported = false;
+	let timer;
+
+	const report = (error) => {
+		if (reported) return;
+		reported = true;
+		clearTimeout(timer);
+		mounted(error);
+	};
+
+	// The element the controls go in: the configured selector, then elements marked with
+	// data-decorum-controls, then the titlebar
+	const findMount = () => {
+		const selectors = [config.mount, "[data-decorum-controls]", "[data-tauri-decorum-tb]"];
+
+		for (const selector of selectors.filter(Boolean)) {
+			const el = document.querySelector(selector);
+			if (el) return el;
 		}
 
-		// Create button func
-		const createButton = (id) => {
-			const btn = document.createElement("button");
-
-			btn.id = "decorum-tb-" + id;
-			btn.style.width = "58px";
-			btn.style.height = "32px";
-			btn.style.border = "none";
-			btn.style.padding = "0px";
-			btn.style.outline = "none";
-			btn.style.display = "flex";
-			btn.style.fontSize = "10px";
-			btn.style.fontWeight = "300";
-			btn.style.cursor = "default";
-			btn.style.boxShadow = "none";
-			btn.style.borderRadius = "0px";
-			btn.style.alignItems = "center";
-			btn.style.justifyContent = "center";
-			btn.style.transition = "background 0.1s";
-			btn.style.backgroundColor = "transparent";
-			btn.style.textRendering = "optimizeLegibility";
-			btn.style.fontFamily = "'Segoe Fluent Icons', 'Segoe MDL2 Assets'";
-
-			let timer;
-			const show_snap_overlay = () => {
-				win.setFocus().then(() =>
-					invoke("plugin:decorum|show_snap_overlay")
-				);
-			};
-
-			// Setup hover events
-			btn.addEventListener("mouseenter", () => {
-				if (id === "close") {
-					btn.style.backgroundColor = "rgba(255,0,0,0.7)";
-				} else {
-					btn.style.backgroundColor = "rgba(0,0,0,0.2)";
-				}
-			});
-
-			btn.addEventListener("mouseleave", () => {
-				btn.style.backgroundColor = "transparent";
-			});
-			switch (id) {
-				case "minimize":
-					btn.innerHTML = "\uE921";
-					btn.setAttribute("aria-label", "Minimize window");
-
-					btn.addEventListener("click", () => {
-						clearTimeout(timer);
-						win.minimize();
-					});
-
-					break;
-				case "maximize":
-					btn.innerHTML = "\uE922";
-					btn.setAttribute("aria-label", "Maximize window");
-					win.onResized(() => {
-						win.isMaximized().then((maximized) => {
-							if (maximized) {
-								btn.innerHTML = "\uE923";
-								btn.setAttribute(
-									"aria-label",
-									"Restore window size"
-								);
-							} else {
-								btn.innerHTML = "\uE922";
-								btn.setAttribute(
-									"aria-label",
-									"Maximize window size"
-								);
-							}
-						});
-					});
-
-					btn.addEventListener("click", () => {
-						clearTimeout(timer);
-						win.toggleMaximize();
-					});
-					btn.addEventListener("mouseleave", () =>
-						clearTimeout(timer)
-					);
-					btn.addEventListener("mouseenter", () => {
-						timer = setTimeout(show_snap_overlay, 620);
-					});
-					break;
-				case "close":
-					btn.innerHTML = "\uE8BB";
-					btn.setAttribute("aria-label", "Close window");
-					btn.addEventListener("click", () => win.close());
-					break;
-			}
-
-			tbEl.appendChild(btn);
-		};
-
-		// Before eval-ing, the line below is modified from the rust side
-		// to only include the controls that are enabled on the window
-		["minimize", "maximize", "close"].forEach(createButton);
-	});
-
-	// Use MutationObserver to watch for changes
-	const observer = new MutationObserver((mutations) => {
-		for (let mutation of mutations) {
-			if (mutation.type === "childList") {
-				const tbEl = document.querySelector("[data-tauri-decorum-tb]");
-				if (tbEl) {
-					debouncedCreateControls();
-					break;
-				}
-			}
+		return null;
+	};
+
+	const reconcile = () => {
+		const mountEl = findMount();
+
+		// Controls copied along with a titlebar, or left over from a previous render
+		for (const el of document.querySelectorAll("decorum-controls")) {
+			if (el !== actions) el.remove();
 		}
-	});
 
-	// data-tauri-decorum-tb may be created before observer starts
-	if (document.querySelector("[data-tauri-decorum-tb]")) {
-		debouncedCreateControls();
-		return;
-	}
+		if (!mountEl) return;
+
+		if (!actions) actions = build();
+
+		// data-decorum-controls="left" or "start" puts the controls before the rest of the container,
+		// in the titlebar they go at the side of the platform's layout
+		const position = mountEl.hasAttribute("data-decorum-controls")
+			? mountEl.getAttribute("data-decorum-controls")
+			: config.side;
+		const start = ["left", "start"].includes(position);
+		const side = start ? "left" : "right";
+
+		if (actions.getAttribute("data-decorum-side") !== side) {
+			actions.setAttribute("data-decorum-side", side);
+		}
+
+		const placed = start
+			? mountEl.firstElementChild === actions
+			: mountEl.lastElementChild === actions;
+
+		if (!placed) {
+			if (start) mountEl.prepend(actions);
+			else mountEl.appendChild(actions);
+		}
+
+		// The titlebar created for lack of one isn't needed once the controls are mounted elsewhere
+		for (const el of document.querySelectorAll("[data-decorum-auto]")) {
+			if (!el.contains(mountEl)) el.remove();
+		}
+
+		// Let the rust side know the controls made it onto the page
+		report(null);
+	};
+
+	// Render the controls again with a new config from the rust side
+	const update = (next) => {
+		config = next;
+		setAttributes();
 
-	observer.observe(document.body, {
-		childList: true,
-		subtree: true,
-	});
+		if (actions) actions.config = config;
 
-	debouncedCreateControls();
-});
+		reconcile();
+	};
+
+	window.__DECORUM_CONTROLS__ = { update };
+
+	const init = () => {
+		setAttributes();
+
+		console.log("DECORUM: Waiting for a place to mount the controls ...");
+
+		timer = setTimeout(() => {
+			report("No element to mount the controls into was found after 10 seconds");
+		}, 10000);
+
+		reconcile();
+
+		// Frameworks may replace the titlebar at any time, keep the controls in the current one
+		new MutationObserver(reconcile).observe(document.documentElement, {
+			childList: true,
+			subtree: true,
+		});
+	};
+
+	if (document.readyState === "loading") {
+		document.addEventListener("DOMContentLoaded", init, { once: true });
+	} else {
+		init();
+	}
+})();
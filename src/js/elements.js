@@ -0,0 +1,430 @@
+(() => {
+	// The <decorum-titlebar> and <decorum-controls> elements, registered on every page before it's parsed.
+	// Their styles live in shadow roots, so the page can only reach them through ::part()
+	// and the --decorum-* custom properties, and its own resets don't restyle them by accident.
+	if (!window.customElements || customElements.get("decorum-controls")) return;
+
+	// Fire and forget, a missing permission only leaves the control doing nothing
+	const invoke = (command, args) =>
+		window.__TAURI__?.core.invoke("plugin:decorum|" + command, args).catch(() => {});
+
+	const labels = {
+		minimize: "Minimize window",
+		maximize: "Maximize window",
+		restore: "Restore window size",
+		close: "Close window",
+	};
+
+	// Attributes set on the page root from the rust side, mirrored on the controls for their styles
+	const mirrored = [
+		"data-decorum-theme",
+		"data-decorum-focused",
+		"data-decorum-maximized",
+		"data-decorum-transparent",
+	];
+
+	// Fixed to the top of the window, styles of the page for the element take precedence
+	const titlebarStyle = `
+		:host {
+			top: 0;
+			left: 0;
+			z-index: 100;
+			width: 100%;
+			height: 32px;
+			display: flex;
+			position: fixed;
+			align-items: end;
+			justify-content: end;
+		}
+
+		[part="drag"] {
+			flex: 1;
+			align-self: stretch;
+		}
+	`;
+
+	// Default metrics and palettes for each style, in light and dark themes. The --decorum-tb-*
+	// properties set with set_titlebar_theme or your own styles take precedence over them.
+	// --decorum-tb-actions-icon-* are still read by the GNOME style for backwards compatibility.
+	const controlsStyle = `
+		:host {
+			display: flex;
+			width: fit-content;
+			align-items: center;
+
+			--_fg-r: var(--decorum-tb-fg, var(--_fg));
+			--_btn-bg-r: var(--decorum-tb-btn-bg, var(--_btn-bg));
+			--_hover-bg-r: var(--decorum-tb-hover-bg, var(--_hover-bg));
+			--_pressed-bg-r: var(--decorum-tb-pressed-bg, var(--_pressed-bg));
+			--_close-hover-bg-r: var(--decorum-tb-close-hover-bg, var(--_close-hover-bg));
+			--_close-hover-fg-r: var(--decorum-tb-close-hover-fg, var(--_close-hover-fg));
+			--_inactive-fg-r: var(--decorum-tb-inactive-fg, var(--_inactive-fg));
+			--_inactive-btn-bg-r: var(--decorum-tb-inactive-btn-bg, var(--_inactive-btn-bg));
+		}
+
+		:host([hidden]) {
+			display: none;
+		}
+
+		:host([data-decorum-style="windows"]) {
+			--_gap: 0px;
+			--_padding: 0px;
+			--_btn-width: 58px;
+			--_btn-height: 32px;
+			--_btn-radius: 0px;
+			--_icon-size: 10px;
+			--_icon-font: "Segoe Fluent Icons", "Segoe MDL2 Assets";
+
+			--_fg: #000000;
+			--_btn-bg: transparent;
+			--_hover-bg: rgba(0, 0, 0, 0.1);
+			--_pressed-bg: rgba(0, 0, 0, 0.2);
+			--_close-hover-bg: rgba(255, 0, 0, 0.7);
+			--_close-hover-fg: #ffffff;
+			--_inactive-fg: color-mix(in srgb, var(--_fg-r) 40%, transparent);
+		}
+
+		:host([data-decorum-style="windows"][data-decorum-theme="dark"]) {
+			--_fg: #ffffff;
+			--_hover-bg: rgba(255, 255, 255, 0.1);
+			--_pressed-bg: rgba(255, 255, 255, 0.2);
+		}
+
+		:host([data-decorum-style="gnome"]) {
+			--_gap: 0.8125em;
+			--_padding: 0 0.5em 0 0;
+			--_btn-width: 1.5em;
+			--_btn-height: 1.5em;
+			--_btn-radius: 50%;
+			--_icon-size: 16px;
+			--_icon-font: inherit;
+
+			--_fg: var(--decorum-tb-actions-icon-fg, #2e3436);
+			--_btn-bg: var(--decorum-tb-actions-icon-bg, rgba(0, 0, 0, 0.08));
+			--_hover-bg: var(--decorum-tb-actions-icon-active-bg, rgba(0, 0, 0, 0.16));
+			--_pressed-bg: rgba(0, 0, 0, 0.24);
+			--_close-hover-bg: var(--_hover-bg-r);
+			--_close-hover-fg: var(--_fg-r);
+			--_inactive-fg: color-mix(in srgb, var(--_fg-r) 50%, transparent);
+		}
+
+		:host([data-decorum-style="gnome"][data-decorum-theme="dark"]) {
+			--_fg: var(--decorum-tb-actions-icon-fg, #ffffff);
+			--_btn-bg: var(--decorum-tb-actions-icon-bg, rgba(255, 255, 255, 0.2));
+			--_hover-bg: var(--decorum-tb-actions-icon-active-bg, rgba(255, 255, 255, 0.4));
+			--_pressed-bg: rgba(255, 255, 255, 0.5);
+		}
+
+		/* Controls placed before the rest of their container, e.g. from a GNOME button layout */
+		:host([data-decorum-side="left"]) {
+			order: -1;
+		}
+
+		:host([data-decorum-style="gnome"][data-decorum-side="left"]) {
+			--_padding: 0 0 0 0.5em;
+		}
+
+		/* The traffic lights sit at the start of the titlebar and only show their glyphs on hover */
+		:host([data-decorum-style="mac"]) {
+			order: -1;
+			align-self: center;
+
+			--_gap: 8px;
+			--_padding: 0 0 0 12px;
+			--_btn-width: 12px;
+			--_btn-height: 12px;
+			--_btn-radius: 50%;
+			--_icon-size: 8px;
+			--_icon-font: inherit;
+
+			--_fg: rgba(0, 0, 0, 0.5);
+			--_btn-bg: transparent;
+			--_close-hover-fg: var(--_fg-r);
+			--_inactive-fg: var(--_fg-r);
+			--_inactive-btn-bg: rgba(0, 0, 0, 0.15);
+		}
+
+		:host([data-decorum-style="mac"][data-decorum-theme="dark"]) {
+			--_inactive-btn-bg: rgba(255, 255, 255, 0.2);
+		}
+
+		[part="actions"] {
+			display: flex;
+			align-items: center;
+			gap: var(--decorum-tb-gap, var(--_gap));
+			padding: var(--decorum-tb-padding, var(--_padding));
+		}
+
+		[part~="button"] {
+			width: var(--decorum-tb-btn-width, var(--_btn-width));
+			height: var(--decorum-tb-btn-height, var(--_btn-height));
+			border: none;
+			padding: 0px;
+			outline: none;
+			display: flex;
+			cursor: default;
+			box-shadow: none;
+			align-items: center;
+			justify-content: center;
+			transition: background 0.1s;
+			border-radius: var(--decorum-tb-btn-radius, var(--_btn-radius));
+			font-family: var(--decorum-tb-icon-font, var(--_icon-font));
+			font-size: var(--decorum-tb-icon-size, var(--_icon-size));
+			font-weight: 300;
+			text-rendering: optimizeLegibility;
+			color: var(--_fg-r);
+			background-color: var(--_btn-bg-r);
+		}
+
+		[part~="button"] svg {
+			width: var(--decorum-tb-icon-size, var(--_icon-size));
+			height: var(--decorum-tb-icon-size, var(--_icon-size));
+		}
+
+		[part~="button"] svg path {
+			fill: currentColor;
+		}
+
+		[part~="button"]:focus-visible {
+			outline: 2px solid var(--decorum-accent, Highlight);
+			outline-offset: -2px;
+		}
+
+		/* Dimmed while the window isn't focused, as native titlebars are */
+		:host([data-decorum-focused="false"]) [part~="button"] {
+			color: var(--_inactive-fg-r);
+		}
+
+		:host([data-decorum-focused="false"]) [part~="button"]:hover {
+			color: var(--_fg-r);
+		}
+
+		[part~="button"]:hover {
+			background-color: var(--_hover-bg-r);
+		}
+
+		[part~="button"]:active {
+			background-color: var(--_pressed-bg-r);
+		}
+
+		[part~="close"]:hover {
+			color: var(--_close-hover-fg-r);
+			background-color: var(--_close-hover-bg-r);
+		}
+
+		[part~="button"]:disabled {
+			opacity: var(--decorum-tb-disabled-opacity, 0.4);
+			color: var(--_fg-r);
+			background-color: var(--_btn-bg-r);
+		}
+
+		/* Each traffic light keeps its color when hovered or pressed */
+		:host([data-decorum-style="mac"]) [part~="button"] {
+			--_hover-bg-r: var(--_btn-bg-r);
+			--_pressed-bg-r: var(--_btn-bg-r);
+			--_close-hover-bg-r: var(--_btn-bg-r);
+		}
+
+		:host([data-decorum-style="mac"]) [part~="close"] {
+			--_btn-bg-r: #ff5f57;
+		}
+
+		:host([data-decorum-style="mac"]) [part~="minimize"] {
+			--_btn-bg-r: #febc2e;
+		}
+
+		:host([data-decorum-style="mac"]) [part~="maximize"] {
+			--_btn-bg-r: #28c840;
+		}
+
+		:host([data-decorum-style="mac"]) [part~="button"]:active {
+			filter: brightness(0.85);
+		}
+
+		:host([data-decorum-style="mac"]) [part~="button"] svg {
+			opacity: 0;
+		}
+
+		:host([data-decorum-style="mac"]:hover) [part~="button"] svg {
+			opacity: 1;
+		}
+
+		:host([data-decorum-style="mac"][data-decorum-focused="false"]:not(:hover)) [part~="button"] {
+			background-color: var(--_inactive-btn-bg-r);
+		}
+
+		:host([data-decorum-style="mac"]) [part~="button"]:disabled {
+			opacity: 1;
+			background-color: var(--_inactive-btn-bg-r);
+		}
+
+		/* Drawn on top of whatever is behind a transparent window, the outline keeps them visible */
+		:host([data-decorum-transparent]) [part~="control"] {
+			filter: var(--decorum-transparent-filter, drop-shadow(0 0 1px rgba(0, 0, 0, 0.6)));
+		}
+	`;
+
+	// A draggable bar at the top of the window, holding the controls
+	class DecorumTitlebar extends HTMLElement {
+		constructor() {
+			super();
+
+			// Tauri 2.12 and later only drag from the element that was pressed, found through the
+			// composed path, so the drag area carries the attribute itself. The host keeps it too,
+			// for older versions that check the event target.
+			this.attachShadow({ mode: "open" });
+			this.shadowRoot.innerHTML = `<style>${titlebarStyle}</style><div part="drag" data-tauri-drag-region></div><slot></slot>`;
+		}
+
+		connectedCallback() {
+			// Found by the controls and the window state like any other titlebar
+			const defaults = {
+				"data-tauri-decorum-tb": "",
+				"data-tauri-drag-region": "",
+				role: "group",
+				"aria-label": "Window controls",
+			};
+
+			for (const [name, value] of Object.entries(defaults)) {
+				if (!this.hasAttribute(name)) this.setAttribute(name, value);
+			}
+		}
+	}
+
+	// The window controls and custom buttons, rendered from the config set by the rust side
+	class DecorumControls extends HTMLElement {
+		#config = null;
+		#observer = null;
+		#snapTimer = null;
+
+		constructor() {
+			super();
+
+			this.attachShadow({ mode: "open" });
+			this.shadowRoot.innerHTML = `<style>${controlsStyle}</style><div part="actions"></div>`;
+
+			// Pick up changes to the window flags, e.g. set_maximizable(false), before the controls are used
+			this.addEventListener("pointerenter", () => invoke("sync_controls"));
+		}
+
+		get config() {
+			return this.#config;
+		}
+
+		set config(config) {
+			this.#config = config;
+			this.setAttribute("data-decorum-style", config.style);
+			this.#render();
+		}
+
+		connectedCallback() {
+			this.#observer = new MutationObserver(() => this.#mirror());
+			this.#observer.observe(document.documentElement, {
+				attributes: true,
+				attributeFilter: mirrored,
+			});
+
+			this.#mirror();
+		}
+
+		disconnectedCallback() {
+			this.#observer?.disconnect();
+			clearTimeout(this.#snapTimer);
+		}
+
+		#mirror() {
+			for (const name of mirrored) {
+				const value = document.documentElement.getAttribute(name);
+
+				if (value === null) this.removeAttribute(name);
+				else if (this.getAttribute(name) !== value) this.setAttribute(name, value);
+			}
+
+			// The maximize button turns into a restore button while the window is maximized,
+			// its icon is only replaced when it changes so hover and focus survive other updates
+			const maximize = this.shadowRoot.querySelector('[data-decorum-control="maximize"]');
+			if (maximize && this.#config) {
+				const icon =
+					this.getAttribute("data-decorum-maximized") === "true" ? "restore" : "maximize";
+
+				if (maximize.getAttribute("data-decorum-icon") === icon) return;
+
+				maximize.setAttribute("data-decorum-icon", icon);
+				maximize.innerHTML = this.#config.icons[icon] || "";
+				maximize.setAttribute("aria-label", labels[icon]);
+			}
+		}
+
+		#render() {
+			const config = this.#config;
+			const actions = this.shadowRoot.querySelector('[part="actions"]');
+
+			clearTimeout(this.#snapTimer);
+			actions.replaceChildren();
+
+			const createButton = (id, label, kind) => {
+				const btn = document.createElement("button");
+
+				btn.setAttribute("part", `button ${kind} ${id}`);
+				btn.setAttribute("data-decorum-control", id);
+				btn.setAttribute("aria-label", label);
+				btn.innerHTML = config.icons[id] || "";
+
+				actions.appendChild(btn);
+				return btn;
+			};
+
+			// The rust side decides what a control does, see on_control_action
+			const createControl = (id) => {
+				const btn = createButton(id, labels[id], "control");
+				btn.disabled = config.disabled.includes(id);
+
+				btn.addEventListener("click", () => {
+					btn.blur();
+					clearTimeout(this.#snapTimer);
+					invoke("control_action", { control: id });
+				});
+
+				// Hovering the maximize button opens the Snap Layouts flyout on Windows
+				if (id === "maximize" && config.style === "windows" && config.platform === "windows") {
+					const showSnapOverlay = () => {
+						window.__TAURI__?.window
+							.getCurrentWindow()
+							.setFocus()
+							.then(() => invoke("show_snap_overlay"))
+							.catch(() => {});
+					};
+
+					btn.addEventListener("mouseleave", () => clearTimeout(this.#snapTimer));
+					btn.addEventListener("mouseenter", () => {
+						if (btn.disabled) return;
+						this.#snapTimer = setTimeout(showSnapOverlay, 620);
+					});
+				}
+			};
+
+			// Custom buttons added from rust, clicks are handled on the rust side
+			const createCustomButton = (button) => {
+				const btn = createButton(button.id, button.tooltip || button.id, "custom");
+
+				if (button.tooltip) btn.title = button.tooltip;
+
+				btn.addEventListener("click", () => {
+					btn.blur();
+					invoke("titlebar_button_click", { id: button.id });
+				});
+			};
+
+			const at = (position) => config.buttons.filter((b) => b.position === position);
+
+			at("start").forEach(createCustomButton);
+			config.controls.forEach(createControl);
+			at("end").forEach(createCustomButton);
+
+			this.#mirror();
+		}
+	}
+
+	customElements.define("decorum-titlebar", DecorumTitlebar);
+	customElements.define("decorum-controls", DecorumControls);
+})();
@@ -0,0 +1,44 @@
+(() => {
+	// Before eval-ing, the values below are replaced from the rust side
+	// with the current frame of the window (in logical pixels)
+	const shadow = "@frame-shadow";
+	const radius = "@frame-radius";
+
+	const apply = () => {
+		const root = document.documentElement;
+
+		let style = document.getElementById("decorum-frame-style");
+
+		if (!style) {
+			style = document.createElement("style");
+			style.id = "decorum-frame-style";
+			style.innerHTML = `
+				:root[data-decorum-frame] {
+					background: transparent !important;
+				}
+
+				:root[data-decorum-frame] body {
+					position: fixed;
+					inset: var(--decorum-frame-shadow);
+					margin: 0;
+					overflow: auto;
+					contain: paint;
+					border-radius: var(--decorum-frame-radius);
+					background: var(--decorum-frame-background, Canvas);
+					box-shadow: 0 2px 8px rgba(0, 0, 0, 0.35), 0 0 0 1px rgba(0, 0, 0, 0.12);
+				}
+			`;
+			document.head.appendChild(style);
+		}
+
+		root.style.setProperty("--decorum-frame-shadow", shadow + "px");
+		root.style.setProperty("--decorum-frame-radius", radius + "px");
+		root.toggleAttribute("data-decorum-frame", shadow !== "0" || radius !== "0");
+	};
+
+	if (document.readyState === "loading") {
+		document.addEventListener("DOMContentLoaded", apply, { once: true });
+	} else {
+		apply();
+	}
+})();
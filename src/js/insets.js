@@ -0,0 +1,141 @@
+(() => {
+	// Publishes the space taken by the window controls as css variables,
+	// and reports where the titlebar and the controls are to the rust side.
+
+	// Before eval-ing, the line below is replaced from the rust side with the space
+	// taken by the native window controls, e.g. the macOS traffic lights, in css pixels
+	const native = "@decorum-native-insets";
+
+	// Evaluated again when the native controls move, only the first run sets up the observers
+	const installed = window.__DECORUM_INSETS__;
+	const insets = (window.__DECORUM_INSETS__ = { native });
+
+	const update = () => {
+		const tbEl = document.querySelector("[data-tauri-decorum-tb]");
+		const actions = document.querySelector("decorum-controls");
+
+		let left = insets.native.left;
+		let right = insets.native.right;
+		let height = Math.max(insets.native.height, tbEl?.getBoundingClientRect().height || 0);
+
+		// Controls drawn in html sit at either end of the titlebar. When mounted elsewhere,
+		// they only take space from the titlebar area while they're at the top of the window.
+		const rect = actions?.getBoundingClientRect();
+		const atTop = rect && (tbEl?.contains(actions) || rect.top < rect.height);
+
+		if (atTop) {
+			if (!tbEl) height = Math.max(height, rect.bottom);
+
+			if (rect.width > 0 && rect.left + rect.width / 2 < window.innerWidth / 2) {
+				left = Math.max(left, rect.right);
+			} else if (rect.width > 0) {
+				right = Math.max(right, window.innerWidth - rect.left);
+			}
+		}
+
+		const vars = {
+			"--decorum-titlebar-height": height,
+			"--decorum-inset-left": left,
+			"--decorum-inset-right": right,
+			// Same as env(titlebar-area-*) from the Window Controls Overlay API
+			"--decorum-titlebar-area-x": left,
+			"--decorum-titlebar-area-y": 0,
+			"--decorum-titlebar-area-width": Math.max(window.innerWidth - left - right, 0),
+			"--decorum-titlebar-area-height": height,
+		};
+
+		for (const [name, value] of Object.entries(vars)) {
+			document.documentElement.style.setProperty(name, Math.round(value) + "px");
+		}
+
+		report(tbEl, actions);
+	};
+
+	const bounds = (el) => {
+		const rect = el.getBoundingClientRect();
+		return { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+	};
+
+	// Let the rust side know where the titlebar and the controls are, when they move
+	const report = (tbEl, actions) => {
+		const buttons = actions?.shadowRoot?.querySelectorAll("[data-decorum-control]") ?? [];
+		const metrics = {
+			titlebar: tbEl ? bounds(tbEl) : null,
+			controls: Object.fromEntries(
+				[...buttons].map((btn) => [btn.getAttribute("data-decorum-control"), bounds(btn)]),
+			),
+		};
+
+		const json = JSON.stringify(metrics);
+		if (json === insets.reported) return;
+
+		insets.reported = json;
+		window.__TAURI__?.core.invoke("plugin:decorum|report_metrics", { metrics }).catch(() => {});
+	};
+
+	insets.update = update;
+
+	if (installed) {
+		update();
+		return;
+	}
+
+	const observe = () => {
+		const update = () => window.__DECORUM_INSETS__.update();
+
+		const watched = new WeakSet();
+		const resizeObserver = new ResizeObserver(update);
+
+		// Follow the titlebar and the controls as they are rendered again or replaced
+		const watch = () => {
+			const tbEl = document.querySelector("[data-tauri-decorum-tb]");
+			const elements = [tbEl, document.querySelector("decorum-controls")];
+
+			for (const el of elements) {
+				if (el && !watched.has(el)) {
+					watched.add(el);
+					resizeObserver.observe(el);
+				}
+			}
+		};
+
+		update();
+		watch();
+
+		window.addEventListener("resize", update);
+
+		// Controls moved to another spot keep their size, so the metrics are read again too,
+		// but only for changes that can move them: the titlebar or the controls added or removed,
+		// or children changed in one of their ancestors. At most once per frame, as reading them
+		// forces a layout.
+		const selector = "[data-tauri-decorum-tb], decorum-controls";
+		const holds = (node) =>
+			node.nodeType === Node.ELEMENT_NODE &&
+			(node.matches(selector) || node.querySelector(selector));
+		const moves = (record) =>
+			[...record.addedNodes, ...record.removedNodes].some(holds) ||
+			[...document.querySelectorAll(selector)].some((el) => record.target.contains(el));
+
+		let scheduled = false;
+		new MutationObserver((records) => {
+			watch();
+
+			if (scheduled || !records.some(moves)) return;
+
+			scheduled = true;
+			requestAnimationFrame(() => {
+				scheduled = false;
+				update();
+			});
+		}).observe(document.documentElement, {
+			childList: true,
+			subtree: true,
+		});
+	};
+
+	if (document.readyState === "loading") {
+		document.addEventListener("DOMContentLoaded", observe, { once: true });
+	} else {
+		observe();
+	}
+})();
@@ -0,0 +1,43 @@
+(() => {
+	// Before eval-ing, the line below is replaced from the rust side
+	// with the maximized, minimized, fullscreen and focused state of the window
+	const state = "@decorum-state";
+
+	// Evaluated again on every change, only the first run sets up the observer
+	const installed = window.__DECORUM_STATE__;
+	window.__DECORUM_STATE__ = state;
+
+	const apply = () => {
+		const elements = [
+			document.documentElement,
+			...document.querySelectorAll("[data-tauri-decorum-tb]"),
+		];
+
+		// Runs on every change to the page, attributes already in place are left alone
+		for (const el of elements) {
+			for (const [key, value] of Object.entries(window.__DECORUM_STATE__)) {
+				const name = "data-decorum-" + key;
+				if (el.getAttribute(name) !== String(value)) el.setAttribute(name, value);
+			}
+		}
+	};
+
+	apply();
+
+	if (installed) return;
+
+	// The titlebar may not be there yet, or be replaced when the page renders again
+	const observe = () => {
+		apply();
+		new MutationObserver(apply).observe(document.documentElement, {
+			childList: true,
+			subtree: true,
+		});
+	};
+
+	if (document.readyState === "loading") {
+		document.addEventListener("DOMContentLoaded", observe, { once: true });
+	} else {
+		observe();
+	}
+})();
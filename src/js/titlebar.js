@@ -1,38 +1,254 @@
+(() => {
+	// Before eval-ing, the line below is replaced from the rust side with the selector
+	// of the element the controls are mounted into, if one is set, and whether the page
+	// renders the titlebar and the controls itself
+	const options = "@decorum-titlebar";
 
-document.addEventListener("DOMContentLoaded", () => {
-	let tbEl = document.querySelector("[data-tauri-decorum-tb]");
-
-	if (!tbEl) {
-		console.log(
-			"DECORUM: Element with data-tauri-decorum-tb not found. Creating one.",
-		);
-
-		// Create titlebar element
-		tbEl = document.createElement("div");
-		tbEl.setAttribute("data-tauri-decorum-tb", "");
-		tbEl.setAttribute("role", "group");
-		tbEl.setAttribute("lang", "en");
-		tbEl.setAttribute("aria-label", "Window controls");
-		tbEl.style.top = 0;
-		tbEl.style.left = 0;
-		tbEl.style.zIndex = 100;
-		tbEl.style.width = "100%";
-		tbEl.style.height = "32px";
-		tbEl.style.display = "flex";
-		tbEl.style.position = "fixed";
-		tbEl.style.alignItems = "end";
-		tbEl.style.justifyContent = "end";
-		tbEl.style.backgroundColor = "transparent";
-
-		// Create draggable area
-		const drag = document.createElement("div");
-		drag.style.width = "100%";
-		drag.style.height = "100%";
-		drag.style.background = "transparent";
-		drag.setAttribute("data-tauri-drag-region", "");
-		tbEl.appendChild(drag);
-
-		// add tbEl to the body
-		document.body.prepend(tbEl);
+	// Let the rust side know the titlebar made it onto the page
+	const mounted = (error) => {
+		window.__TAURI__?.core
+			.invoke("plugin:decorum|titlebar_mounted", { part: "titlebar", error })
+			.catch(() => {});
+	};
+
+	// Electron-style app-region: drag and no-drag, next to data-tauri-drag-region. Webviews without
+	// app-region support drop the declaration, so it's also read from inline styles and the page's
+	// stylesheets, where the last matching rule wins.
+	const installAppRegion = () => {
+		if (window.__DECORUM_APP_REGION__) return;
+		window.__DECORUM_APP_REGION__ = true;
+
+		const declaration = /(?:-webkit-)?app-region\s*:\s*(no-drag|drag)/gi;
+		const linked = new Map();
+
+		const regionIn = (declarations) => {
+			let region = null;
+			for (const match of declarations.matchAll(declaration)) region = match[1].toLowerCase();
+			return region;
+		};
+
+		// Index of the brace closing the block opened at `open`
+		const blockEnd = (css, open) => {
+			let depth = 0;
+			for (let i = open; i < css.length; i++) {
+				if (css[i] === "{") depth++;
+				else if (css[i] === "}" && --depth === 0) return i;
+			}
+			return css.length;
+		};
+
+		// Rules declaring an app-region, with the media queries they're nested in.
+		// @supports blocks are read when the webview supports them, other at-rules are skipped.
+		const parse = (css, media = []) => {
+			const found = [];
+
+			for (let i = 0, open; (open = css.indexOf("{", i)) !== -1; ) {
+				const close = blockEnd(css, open);
+				const body = css.slice(open + 1, close);
+
+				// Statements like @import end with a semicolon, and don't open a block
+				const prelude = css.slice(i, open);
+				const selector = prelude.slice(prelude.lastIndexOf(";") + 1).trim();
+				i = close + 1;
+
+				const [, atRule, condition] = selector.match(/^@([\w-]+)\s*([\s\S]*)$/) || [];
+				if (atRule === "media") {
+					found.push(...parse(body, [...media, condition]));
+				} else if (atRule === "supports") {
+					if (window.CSS?.supports(condition)) found.push(...parse(body, media));
+				} else if (!atRule) {
+					const region = regionIn(body);
+					if (region) found.push({ selector, region, media });
+				}
+			}
+
+			return found;
+		};
+
+		// Parsed once per stylesheet, and again only when the stylesheets on the page change
+		const parsed = new WeakMap();
+		let cached = null;
+
+		// Rules declaring an app-region, in the order they appear on the page
+		const rules = () => {
+			if (cached) return cached;
+
+			cached = [];
+			for (const sheet of document.styleSheets) {
+				if (!parsed.has(sheet)) {
+					const node = sheet.ownerNode;
+					const text = node?.localName === "style" ? node.textContent : linked.get(sheet.href);
+
+					// Linked stylesheets are fetched once, and used from the next press on
+					if (text === undefined && sheet.href) {
+						linked.set(sheet.href, "");
+						fetch(sheet.href)
+							.then((res) => res.text())
+							.then((css) => {
+								linked.set(sheet.href, css);
+								parsed.delete(sheet);
+								cached = null;
+							})
+							.catch(() => {});
+						continue;
+					}
+
+					parsed.set(sheet, parse((text || "").replace(/\/\*[\s\S]*?\*\//g, "")));
+				}
+
+				cached.push(...parsed.get(sheet));
+			}
+
+			return cached;
+		};
+
+		const isStylesheet = (node) =>
+			node.localName === "style" || (node.localName === "link" && /stylesheet/i.test(node.rel));
+
+		new MutationObserver((records) => {
+			const changed = records.some(
+				(record) =>
+					isStylesheet(record.target) ||
+					record.target.parentNode?.localName === "style" ||
+					[...record.addedNodes, ...record.removedNodes].some(isStylesheet),
+			);
+			if (!changed) return;
+
+			// Edited <style> elements get a new sheet, so only the cached list is dropped
+			cached = null;
+		}).observe(document.documentElement, {
+			childList: true,
+			subtree: true,
+			characterData: true,
+			attributeFilter: ["href", "media", "disabled"],
+		});
+
+		const regionOf = (el, rules) => {
+			// The window controls are never dragged from
+			if (el.localName === "decorum-controls") return "no-drag";
+
+			const computed = getComputedStyle(el);
+			const native =
+				computed.getPropertyValue("app-region") ||
+				computed.getPropertyValue("-webkit-app-region");
+			if (native === "drag" || native === "no-drag") return native;
+
+			let region = regionIn(el.getAttribute("style") || "");
+			if (region) return region;
+
+			// The last matching rule wins, regardless of the specificity of its selector
+			for (const rule of rules) {
+				try {
+					if (!rule.media.every((query) => matchMedia(query).matches)) continue;
+					if (el.matches(rule.selector)) region = rule.region;
+				} catch {
+					// Selectors the webview doesn't support
+				}
+			}
+
+			return region;
+		};
+
+		document.addEventListener("mousedown", (e) => {
+			if (e.button !== 0) return;
+
+			// Left to tauri's own drag handling, which looks at the element pressed, inside shadow roots too
+			const pressed = e.composedPath()[0];
+			if (pressed instanceof Element && pressed.hasAttribute("data-tauri-drag-region")) return;
+
+			const found = rules();
+
+			// The closest element declaring an app-region decides, no-drag keeps its descendants out
+			for (const el of e.composedPath()) {
+				if (!(el instanceof Element)) continue;
+
+				const region = regionOf(el, found);
+				if (region === "no-drag") return;
+				if (region !== "drag") continue;
+
+				e.preventDefault();
+
+				// Double-clicking maximizes the window, as a native titlebar does
+				if (e.detail === 2) {
+					window.__TAURI__?.core
+						.invoke("plugin:decorum|control_action", { control: "maximize" })
+						.catch(() => {});
+				} else {
+					window.__TAURI__?.window.getCurrentWindow().startDragging().catch(() => {});
+				}
+				return;
+			}
+		});
+
+		// Start fetching the linked stylesheets before the first press
+		const preload = () => rules();
+		if (document.readyState === "loading") {
+			document.addEventListener("DOMContentLoaded", preload, { once: true });
+		} else {
+			preload();
+		}
+	};
+
+	installAppRegion();
+
+	const mount = () => {
+		// Nothing is added to the page in headless mode, its own components draw the titlebar
+		if (options.headless) {
+			mounted(null);
+			return;
+		}
+
+		// Titlebar background while the window is focused or not, set with set_titlebar_theme.
+		// Styles of your own for the titlebar take precedence over this.
+		if (!document.getElementById("decorum-titlebar-style")) {
+			const style = document.createElement("style");
+			style.id = "decorum-titlebar-style";
+			style.innerHTML = `
+				:where([data-tauri-decorum-tb]) {
+					background-color: var(--decorum-tb-bg, transparent);
+				}
+
+				:where(:root[data-decorum-focused="false"]) :where([data-tauri-decorum-tb]) {
+					background-color: var(--decorum-tb-inactive-bg, var(--decorum-tb-bg, transparent));
+				}
+			`;
+			document.head.appendChild(style);
+		}
+
+		let tbEl = document.querySelector("[data-tauri-decorum-tb]");
+
+		// Controls mounted elsewhere, e.g. in a sidebar header, leave dragging to the page.
+		// A mount set from the rust side may only be rendered later, so it's not waited for.
+		const hasMount = options.mount || document.querySelector("[data-decorum-controls]");
+
+		if (!tbEl && hasMount) {
+			mounted(null);
+			return;
+		}
+
+		if (!tbEl) {
+			console.log(
+				"DECORUM: Element with data-tauri-decorum-tb not found. Creating one.",
+			);
+
+			// A draggable bar fixed to the top of the window, see elements.js
+			tbEl = document.createElement("decorum-titlebar");
+			tbEl.setAttribute("lang", "en");
+
+			// Taken down by the controls script if the page renders a mount point later on
+			tbEl.setAttribute("data-decorum-auto", "");
+
+			// add tbEl to the body
+			document.body.prepend(tbEl);
+		}
+
+		mounted(tbEl.isConnected ? null : "[data-tauri-decorum-tb] could not be added to the page");
+	};
+
+	// Injected before or after the page is parsed, depending on when the page load is reported
+	if (document.readyState === "loading") {
+		document.addEventListener("DOMContentLoaded", mount, { once: true });
+	} else {
+		mount();
 	}
-});
+})();
@@ -0,0 +1,5 @@
+(() => {
+	// The window controls are drawn on top of whatever is behind the window,
+	// <decorum-controls> gives them an outline so they don't disappear on similar colors.
+	document.documentElement.setAttribute("data-decorum-transparent", "");
+})();
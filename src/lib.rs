@@ -1,5 +1,8 @@
-use tauri::plugin::{Builder, TauriPlugin};
-use tauri::{Emitter, Error, Listener, Runtime, WebviewWindow};
+use std::future::Future;
+use std::pin::Pin;
+
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{AppHandle, Emitter, Error, Listener, Manager, Runtime, WebviewWindow};
 
 #[cfg(target_os = "macos")]
 mod traffic;
@@ -7,137 +10,152 @@ mod traffic;
 #[cfg(target_os = "linux")]
 mod dconf;
 
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "linux")]
+mod portal;
+
+mod actions;
+mod buttons;
 mod commands;
+mod config;
+mod control_state;
+mod controls;
+mod icons;
+mod insets;
+mod metrics;
+mod ready;
+mod theme;
+mod window_state;
+mod windows;
+
+pub use actions::{ActionDecision, Control, ControlAction};
+pub use buttons::{ButtonPosition, TitlebarButton};
+pub use config::ControlStyle;
+pub use control_state::ControlState;
+pub use controls::ControlsLayout;
+pub use icons::{Icon, IconSet};
+pub use metrics::{Bounds, TitlebarMetrics};
+pub use ready::TitlebarStatus;
+pub use theme::DecorumTheme;
+pub use window_state::WindowState;
 
 #[cfg(target_os = "macos")]
 #[macro_use]
 extern crate objc;
 
+/// Extensions to [`tauri::AppHandle`] to access the app-wide decorum APIs.
+pub trait AppHandleExt {
+    fn set_titlebar_theme(&self, theme: DecorumTheme) -> Result<(), Error>;
+}
+
+impl<R: Runtime> AppHandleExt for AppHandle<R> {
+    /// Set the colors of the titlebar and window controls for the whole app.
+    /// The theme is applied to all windows with an overlay titlebar, including ones created later.
+    /// Themes set on individual windows with [`WebviewWindowExt::set_titlebar_theme`] take precedence.
+    fn set_titlebar_theme(&self, theme: DecorumTheme) -> Result<(), Error> {
+        theme::set_app(self, theme);
+        Ok(())
+    }
+}
+
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the decorum APIs.
 pub trait WebviewWindowExt {
     fn create_overlay_titlebar(&self) -> Result<&WebviewWindow, Error>;
+    fn set_titlebar_theme(&self, theme: DecorumTheme) -> Result<&WebviewWindow, Error>;
+    fn add_titlebar_button<F>(
+        &self,
+        button: TitlebarButton,
+        on_click: F,
+    ) -> Result<&WebviewWindow, Error>
+    where
+        F: Fn(&WebviewWindow) + Send + Sync + 'static;
+    fn set_control_state(
+        &self,
+        control: Control,
+        state: ControlState,
+    ) -> Result<&WebviewWindow, Error>;
+    fn titlebar_metrics(&self) -> Option<TitlebarMetrics>;
+    fn on_titlebar_ready<F>(&self, callback: F) -> Result<&WebviewWindow, Error>
+    where
+        F: Fn(&WebviewWindow, &TitlebarStatus) + Send + Sync + 'static;
+    fn titlebar_ready(&self) -> Pin<Box<dyn Future<Output = TitlebarStatus> + Send>>;
     #[cfg(target_os = "macos")]
     fn set_traffic_lights_inset(&self, x: f32, y: f32) -> Result<&WebviewWindow, Error>;
-    #[cfg(target_os = "macos")]
     fn make_transparent(&self) -> Result<&WebviewWindow, Error>;
     #[cfg(target_os = "macos")]
     fn set_window_level(&self, level: u32) -> Result<&WebviewWindow, Error>;
+    #[cfg(target_os = "linux")]
+    fn enable_csd_shadow(&self) -> Result<&WebviewWindow, Error>;
+    #[cfg(target_os = "linux")]
+    fn set_corner_radius(&self, radius: u32) -> Result<&WebviewWindow, Error>;
 }
 
-impl<'a> WebviewWindowExt for WebviewWindow {
+impl WebviewWindowExt for WebviewWindow {
     /// Create a custom titlebar overlay.
     /// This will remove the default titlebar and create a draggable area for the titlebar.
-    /// On Windows, it will also create custom window controls.
+    /// On Windows and Linux, it will also create custom window controls.
+    /// On macOS, the traffic lights are replaced with custom controls when a [`ControlStyle`] other than `Native` is set.
+    /// With [`Builder::show_when_ready`], the window is kept hidden until the titlebar is mounted.
     fn create_overlay_titlebar(&self) -> Result<&WebviewWindow, Error> {
+        // Hidden before the decorations are removed, so they never flicker
+        let show_when_ready = self
+            .try_state::<config::Config>()
+            .is_some_and(|config| config.show_when_ready);
+
+        if show_when_ready {
+            ready::show_when_ready(self)?;
+        }
+
         #[cfg(target_os = "windows")]
         self.set_decorations(false)?;
 
+        #[cfg(target_os = "macos")]
+        if controls::style(self).rendered().is_some() {
+            ensure_main_thread(self, move |win| {
+                let ns_window = win.ns_window()?;
+                traffic::hide_traffic_lights(traffic::UnsafeWindowHandle(ns_window));
+                Ok(win)
+            })?;
+        }
+
         let win2 = self.clone();
 
         self.listen("decorum-page-load", move |_event| {
             // println!("decorum-page-load event received")
 
             // Create a transparent draggable area for the titlebar
-            let script_tb = include_str!("js/titlebar.js");
+            let script_tb = controls::titlebar_script(&win2);
 
             win2.eval(script_tb)
                 .unwrap_or_else(|e| println!("decorum error: {:?}", e));
 
-            // Custom window controls for linux
-            #[cfg(target_os = "linux")]
-            {
-                use linicon::{lookup_icon, IconType};
-                use std::io::prelude::*;
-                let mut control_script = include_str!("./js/linux-controls.js").to_string();
+            // Let the window controls pick the palette for the current theme
+            if let Ok(theme) = win2.theme() {
+                set_theme_attribute(&win2, theme);
+            }
 
-                let mut controls = Vec::new();
-                if win2.is_minimizable().unwrap_or(false) {
-                    controls.push("minimize".to_string());
-                }
+            // Maximized, minimized, fullscreen and focused state as data-decorum-* attributes
+            window_state::apply(&win2);
 
-                if win2.is_maximizable().unwrap_or(false) && win2.is_resizable().unwrap_or(false) {
-                    controls.push("maximize".to_string());
-                }
-
-                if win2.is_closable().unwrap_or(false) {
-                    controls.push("close".to_string());
-                }
+            theme::apply(&win2);
 
-                controls.push("restore".to_string());
-
-                for control in controls.iter() {
-                    if let Some(Ok(control_icon)) =
-                        lookup_icon(format!("window-{}-symbolic", control))
-                            .into_iter()
-                            .find(|icon| match icon {
-                                Ok(icon) => icon.icon_type == IconType::SVG,
-                                Err(_) => false,
-                            })
-                    {
-                        let mut icon_data = String::new();
-                        let mut f = std::fs::File::open(control_icon.path).unwrap();
-                        let _ = f.read_to_string(&mut icon_data);
-
-                        control_script =
-                            control_script.replace(&format!("@win-{}", control), &icon_data);
-                    };
-                }
+            // Custom window controls, in the style set in the plugin config
+            if let Some(script) = controls::script(&win2) {
+                win2.eval(script)
+                    .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+            }
 
-                controls.remove(controls.len() - 1);
+            // Space taken by the window controls as CSS variables
+            insets::publish(&win2);
 
-                // return this string style 'appmenu:minimize,maximize,close'
-                if let Ok(app_menu_config) =
-                    dconf::read("/org/gnome/desktop/wm/preferences/button-layout")
-                {
-                    controls = app_menu_config
-                        .trim_start_matches("appmenu:")
-                        .split(',')
-                        .map(|x| x.to_string())
-                        .collect::<Vec<String>>();
-                };
-
-                let controls = format!("{:?}", controls);
-
-                let control_script = control_script.replacen(
-                    "[\"minimize\", \"maximize\", \"close\"]",
-                    &controls,
-                    1,
-                );
-
-                win2.eval(&control_script).expect("couldn't run js");
-            }
+            // Accent color, color scheme and contrast preferences as CSS variables
+            #[cfg(target_os = "linux")]
+            portal::publish(&win2);
 
-            // On Windows, create custom window controls
             #[cfg(target_os = "windows")]
             {
-                let mut controls = Vec::new();
-
-                if win2.is_minimizable().unwrap_or(false) {
-                    controls.push("minimize");
-                }
-
-                if win2.is_maximizable().unwrap_or(false) && win2.is_resizable().unwrap_or(false) {
-                    controls.push("maximize");
-                }
-
-                if win2.is_closable().unwrap_or(false) {
-                    controls.push("close");
-                }
-
-                let script_controls = include_str!("js/controls.js");
-                let controls = format!("{:?}", controls);
-
-                // this line finds ["minimize", "maximize", "close"] in the file
-                // and replaces it with only the controls enabled for the window
-                let script_controls = script_controls.replacen(
-                    "[\"minimize\", \"maximize\", \"close\"]",
-                    &controls,
-                    1,
-                );
-
-                win2.eval(script_controls.as_str())
-                    .expect("couldn't run js");
-
                 let win3 = win2.clone();
                 win2.on_window_event(move |eve| match eve {
                     tauri::WindowEvent::CloseRequested { .. } => {
@@ -148,9 +166,105 @@ impl<'a> WebviewWindowExt for WebviewWindow {
             }
         });
 
+        let win2 = self.clone();
+
+        self.on_window_event(move |event| match event {
+            tauri::WindowEvent::ThemeChanged(theme) => set_theme_attribute(&win2, *theme),
+            tauri::WindowEvent::Focused(focused) => {
+                window_state::update(&win2, Some(*focused));
+                control_state::sync(&win2);
+            }
+            tauri::WindowEvent::Resized(_) => {
+                let previous = window_state::update(&win2, None);
+
+                // The traffic lights move in and out of fullscreen
+                #[cfg(target_os = "macos")]
+                if previous.is_some_and(|previous| {
+                    previous.fullscreen != window_state::get(&win2).fullscreen
+                }) {
+                    insets::publish(&win2);
+                }
+
+                #[cfg(not(target_os = "macos"))]
+                let _ = previous;
+            }
+            tauri::WindowEvent::ScaleFactorChanged { .. } => insets::publish(&win2),
+            _ => {}
+        });
+
+        windows::register(self);
+
+        Ok(self)
+    }
+
+    /// Set the colors of the titlebar and window controls.
+    /// Colors left unset in the theme fall back to the app-wide theme and then the platform defaults.
+    /// The theme is kept across page loads and applies to the live controls right away.
+    fn set_titlebar_theme(&self, theme: DecorumTheme) -> Result<&WebviewWindow, Error> {
+        theme::set(self, theme);
+        Ok(self)
+    }
+
+    /// Add a custom button next to the window controls, with the same styling and spacing.
+    /// `on_click` runs when the button is clicked, which also emits a `decorum://titlebar-button`
+    /// event to the window with the id of the button.
+    /// A button with the same id is replaced. The controls already on the page are updated right away.
+    /// Fails when the id is one of the window controls (`close`, `maximize`, `minimize`, `restore`)
+    /// or contains whitespace.
+    fn add_titlebar_button<F>(
+        &self,
+        button: TitlebarButton,
+        on_click: F,
+    ) -> Result<&WebviewWindow, Error>
+    where
+        F: Fn(&WebviewWindow) + Send + Sync + 'static,
+    {
+        buttons::add(self, button, on_click)?;
+        controls::refresh(self);
         Ok(self)
     }
 
+    /// Enable, disable or hide one of the window controls.
+    /// Disabled controls are greyed out and do nothing when pressed.
+    /// Controls without a state set follow the window flags, e.g. `set_maximizable(false)` hides the maximize button.
+    /// Changes to the flags are picked up when the window gains focus or the pointer enters the controls.
+    fn set_control_state(
+        &self,
+        control: Control,
+        state: ControlState,
+    ) -> Result<&WebviewWindow, Error> {
+        control_state::set(self, control, state);
+        Ok(self)
+    }
+
+    /// Where the titlebar and the controls drawn by decorum are, in logical pixels.
+    /// The page reports them after layout and whenever they change, which is also
+    /// emitted to the window as a `decorum://metrics` event.
+    /// Returns `None` until the page has reported them.
+    fn titlebar_metrics(&self) -> Option<TitlebarMetrics> {
+        metrics::get(self)
+    }
+
+    /// Run `callback` once the titlebar and the window controls are on the page,
+    /// or with the reason they could not be mounted, e.g. to show the window only once its chrome is in place.
+    /// It runs again after every page load, and right away if the titlebar is already mounted.
+    /// Pages that don't report the titlebar within 15 seconds count as `Failed`, e.g. when
+    /// `withGlobalTauri` is off or the decorum permissions are missing.
+    fn on_titlebar_ready<F>(&self, callback: F) -> Result<&WebviewWindow, Error>
+    where
+        F: Fn(&WebviewWindow, &TitlebarStatus) + Send + Sync + 'static,
+    {
+        ready::on_ready(self, callback);
+        Ok(self)
+    }
+
+    /// Resolves once the titlebar and the window controls are on the page, or fail to be.
+    /// Resolves right away if the titlebar is already mounted, and with `Failed` after 15 seconds at most,
+    /// e.g. for a window without an overlay titlebar.
+    fn titlebar_ready(&self) -> Pin<Box<dyn Future<Output = TitlebarStatus> + Send>> {
+        ready::wait(self)
+    }
+
     /// Set the inset of the traffic lights.
     /// This will move the traffic lights to the specified position.
     /// This is only available on macOS.
@@ -162,43 +276,75 @@ impl<'a> WebviewWindowExt for WebviewWindow {
 
             // Store the custom position in the window state
             traffic::update_traffic_light_positions(win, x.into(), y.into());
-            
+
             // Apply the position immediately
             traffic::position_traffic_lights(ns_window_handle, x.into(), y.into());
 
+            // Let the page know about the space they take now
+            insets::publish(win);
+
             Ok(win)
         })
     }
 
     /// Set the window background to transparent.
-    /// This helper function is different from Tauri's default
-    /// as it doesn't use the `transparent` flag or macOS Private APIs.
-    #[cfg(target_os = "macos")]
+    /// On macOS this works on any window, without the macOS Private APIs that Tauri's
+    /// `transparent: true` needs there.
+    /// On Windows and Linux only the webview is made transparent here, the window itself must be
+    /// created with `transparent: true` to show the desktop behind it. On Linux this fails otherwise.
     fn make_transparent(&self) -> Result<&WebviewWindow, Error> {
-        use cocoa::{
-            appkit::NSColor,
-            base::{id, nil},
-            foundation::NSString,
-        };
-
-        // Make webview background transparent
-        self.with_webview(|webview| unsafe {
-            let id = webview.inner() as *mut objc::runtime::Object;
-            let no: id = msg_send![class!(NSNumber), numberWithBool:0];
-            let _: id =
-                msg_send![id, setValue:no forKey: NSString::alloc(nil).init_str("drawsBackground")];
-        })?;
+        #[cfg(target_os = "macos")]
+        {
+            use cocoa::{
+                appkit::NSColor,
+                base::{id, nil},
+                foundation::NSString,
+            };
+
+            // Make webview background transparent
+            self.with_webview(|webview| unsafe {
+                let id = webview.inner() as *mut objc::runtime::Object;
+                let no: id = msg_send![class!(NSNumber), numberWithBool:0];
+                let _: id = msg_send![id, setValue:no forKey: NSString::alloc(nil).init_str("drawsBackground")];
+            })?;
+
+            // Make window background transparent
+            ensure_main_thread(self, move |win| {
+                let ns_win = win.ns_window()? as id;
+                unsafe {
+                    let win_bg_color =
+                        NSColor::colorWithSRGBRed_green_blue_alpha_(nil, 0.0, 0.0, 0.0, 0.0);
+                    let _: id = msg_send![ns_win, setBackgroundColor: win_bg_color];
+                }
+                Ok(win)
+            })?;
+        }
 
-        // Make window background transparent
+        // Use an RGBA visual for the window and clear the WebKitGTK background
+        #[cfg(target_os = "linux")]
         ensure_main_thread(self, move |win| {
-            let ns_win = win.ns_window()? as id;
-            unsafe {
-                let win_bg_color =
-                    NSColor::colorWithSRGBRed_green_blue_alpha_(nil, 0.0, 0.0, 0.0, 0.0);
-                let _: id = msg_send![ns_win, setBackgroundColor: win_bg_color];
-            }
+            linux::make_transparent(win)?;
             Ok(win)
-        })
+        })?;
+
+        #[cfg(target_os = "windows")]
+        self.set_background_color(Some(tauri::window::Color(0, 0, 0, 0)))?;
+
+        // Keep the custom window controls legible on whatever is behind the window
+        #[cfg(not(target_os = "macos"))]
+        {
+            let script = include_str!("js/transparent.js");
+            let win2 = self.clone();
+
+            self.listen("decorum-page-load", move |_event| {
+                win2.eval(script)
+                    .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+            });
+
+            self.eval(script)?;
+        }
+
+        Ok(self)
     }
 
     /// Set the window level.
@@ -215,31 +361,222 @@ impl<'a> WebviewWindowExt for WebviewWindow {
             Ok(win)
         })
     }
-}
 
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("decorum")
-        .invoke_handler(tauri::generate_handler![commands::show_snap_overlay])
-        .on_page_load(|win, _payload: &tauri::webview::PageLoadPayload| {
-            match win.emit("decorum-page-load", ()) {
-                Ok(_) => {}
-                Err(e) => println!("decorum error: {:?}", e),
-            }
+    /// Draw a client-side shadow around the window.
+    /// The window is made transparent and its contents are inset to leave room for the shadow,
+    /// which is reported to the compositor as frame extents so snapping and maximizing ignore it.
+    /// The shadow is dropped while the window is maximized, fullscreen or tiled.
+    /// This is only available on Linux, for windows without decorations created with `transparent: true`,
+    /// and fails without changing the window otherwise.
+    #[cfg(target_os = "linux")]
+    fn enable_csd_shadow(&self) -> Result<&WebviewWindow, Error> {
+        ensure_main_thread(self, move |win| {
+            linux::enable_csd_shadow(win)?;
+            Ok(win)
         })
-        .on_window_ready(|_win| {
-            #[cfg(target_os = "macos")]
-            traffic::setup_traffic_light_positioner(_win);
-            return;
+    }
+
+    /// Round the corners of the window.
+    /// The window is made transparent and its contents are clipped to the given radius (in logical pixels).
+    /// Clicks in the transparent corners pass through to the windows below.
+    /// Corners are squared while the window is maximized, fullscreen or tiled.
+    /// This is only available on Linux, for windows without decorations created with `transparent: true`,
+    /// and fails without changing the window otherwise.
+    #[cfg(target_os = "linux")]
+    fn set_corner_radius(&self, radius: u32) -> Result<&WebviewWindow, Error> {
+        ensure_main_thread(self, move |win| {
+            linux::set_corner_radius(win, radius)?;
+            Ok(win)
         })
-        .build()
+    }
 }
 
-#[cfg(target_os = "macos")]
+/// Builder for the decorum plugin, to set options from Rust.
+/// Options set here take precedence over the ones in `plugins > decorum` in `tauri.conf.json`.
+pub struct Builder<R: Runtime> {
+    control_style: Option<ControlStyle>,
+    show_when_ready: Option<bool>,
+    controls_mount: Option<String>,
+    headless: Option<bool>,
+    icons: Option<IconSet>,
+    on_control_action: Option<actions::ActionHandler<R>>,
+}
+
+impl<R: Runtime> Default for Builder<R> {
+    fn default() -> Self {
+        Self {
+            control_style: None,
+            show_when_ready: None,
+            controls_mount: None,
+            headless: None,
+            icons: None,
+            on_control_action: None,
+        }
+    }
+}
+
+impl<R: Runtime> Builder<R> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw the window controls in the given style instead of the platform's.
+    pub fn control_style(mut self, style: ControlStyle) -> Self {
+        self.control_style = Some(style);
+        self
+    }
+
+    /// Keep windows with an overlay titlebar hidden until the titlebar and the controls are mounted,
+    /// or for 3 seconds at most, so the window never shows up without its chrome.
+    /// Create the windows with `visible: false` so they aren't shown before `create_overlay_titlebar` either.
+    pub fn show_when_ready(mut self, show_when_ready: bool) -> Self {
+        self.show_when_ready = Some(show_when_ready);
+        self
+    }
+
+    /// Mount the window controls into the first element matching the CSS selector,
+    /// e.g. a sidebar header or a tab strip, instead of the `[data-tauri-decorum-tb]` titlebar.
+    /// Elements with a `data-decorum-controls` attribute are used when nothing matches.
+    pub fn controls_mount(mut self, selector: impl Into<String>) -> Self {
+        self.controls_mount = Some(selector.into());
+        self
+    }
+
+    /// Don't add the titlebar or the window controls to the page, so your own components can render them.
+    /// The page still gets the resolved layout of the controls, the window state and the control actions
+    /// from the JS API, and `on_titlebar_ready` runs as soon as the page is loaded.
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = Some(headless);
+        self
+    }
+
+    /// Use the given icons for the window controls on every platform.
+    pub fn icons(mut self, icons: IconSet) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+
+    /// Decide what happens when the minimize, maximize or close buttons are pressed.
+    /// Return [`ActionDecision::Cancel`] to keep the default from happening,
+    /// e.g. to hide the window to the tray instead of minimizing it, or to ask about unsaved changes before closing.
+    /// Every action is also emitted to the window as a `decorum://control-action` event.
+    pub fn on_control_action<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&WebviewWindow<R>, ControlAction) -> ActionDecision + Send + Sync + 'static,
+    {
+        self.on_control_action = Some(Box::new(handler));
+        self
+    }
+
+    pub fn build(self) -> TauriPlugin<R, Option<config::Config>> {
+        PluginBuilder::<R, Option<config::Config>>::new("decorum")
+            // <decorum-titlebar> and <decorum-controls>, defined before the page is parsed
+            .js_init_script(include_str!("js/elements.js"))
+            .invoke_handler(tauri::generate_handler![
+                commands::show_snap_overlay,
+                commands::titlebar_button_click,
+                commands::control_action,
+                commands::sync_controls,
+                commands::report_metrics,
+                commands::titlebar_mounted,
+                commands::controls_layout,
+                commands::window_state
+            ])
+            .setup(move |app, api| {
+                let mut config = api.config().clone().unwrap_or_default();
+                if let Some(style) = self.control_style {
+                    config.control_style = style;
+                }
+                if let Some(show_when_ready) = self.show_when_ready {
+                    config.show_when_ready = show_when_ready;
+                }
+                if let Some(selector) = self.controls_mount {
+                    config.controls_mount = Some(selector);
+                }
+                if let Some(headless) = self.headless {
+                    config.headless = headless;
+                }
+                if let Some(icons) = self.icons {
+                    config.icons = icons;
+                }
+
+                app.manage(config);
+                app.manage(windows::Windows::<R>::default());
+                app.manage(theme::Themes::default());
+                app.manage(buttons::Buttons::<R>::default());
+                app.manage(actions::ControlActions {
+                    handler: self.on_control_action,
+                });
+
+                #[cfg(target_os = "linux")]
+                {
+                    app.manage(portal::AppearanceState::default());
+                    portal::watch(app);
+                }
+                Ok(())
+            })
+            .on_page_load(|win, payload: &tauri::webview::PageLoadPayload| {
+                // Wait for the new page to report the titlebar, and the controls if there are any.
+                // Windows without an overlay titlebar have nothing to report.
+                let decorated = windows::is_decorated(win, win.label());
+                if decorated && matches!(payload.event(), tauri::webview::PageLoadEvent::Started) {
+                    match controls::style(win).rendered() {
+                        Some(_) if !controls::headless(win) => {
+                            ready::expect(win, &["titlebar", "controls"])
+                        }
+                        _ => ready::expect(win, &["titlebar"]),
+                    }
+                }
+
+                match win.emit("decorum-page-load", ()) {
+                    Ok(_) => {}
+                    Err(e) => println!("decorum error: {:?}", e),
+                }
+
+                #[cfg(target_os = "linux")]
+                linux::on_page_load(win);
+            })
+            .on_event(|app, event| {
+                // Everything kept about a window goes with it
+                if let tauri::RunEvent::WindowEvent {
+                    label,
+                    event: tauri::WindowEvent::Destroyed,
+                    ..
+                } = event
+                {
+                    windows::unregister(app, label);
+                }
+            })
+            .on_window_ready(|_win| {
+                #[cfg(target_os = "macos")]
+                traffic::setup_traffic_light_positioner(_win);
+            })
+            .build()
+    }
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<config::Config>> {
+    Builder::<R>::default().build()
+}
+
+/// Mark the page with the window's theme as `data-decorum-theme="light|dark"`,
+/// which the window controls use to pick their default palette.
+fn set_theme_attribute<R: Runtime>(win: &WebviewWindow<R>, theme: tauri::Theme) {
+    let script = format!(
+        "document.documentElement.setAttribute('data-decorum-theme', '{}')",
+        theme
+    );
+
+    win.eval(script)
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 fn is_main_thread() -> bool {
     std::thread::current().name() == Some("main")
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 fn ensure_main_thread<F>(
     win: &WebviewWindow,
     main_action: F,
@@ -254,13 +591,15 @@ where
         }
         false => {
             let win2 = win.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
 
-            match win.run_on_main_thread(move || {
-                main_action(&win2).unwrap();
-            }) {
-                Ok(_) => Ok(win),
-                Err(e) => Err(e),
-            }
+            // Wait for the main thread, so errors make it back to the caller
+            win.run_on_main_thread(move || {
+                let _ = tx.send(main_action(&win2).map(|_| ()));
+            })?;
+
+            rx.recv().map_err(|_| Error::FailedToReceiveMessage)??;
+            Ok(win)
         }
     }
 }
@@ -0,0 +1,246 @@
+// Client-side decorations for undecorated GTK windows.
+// Most of this emulates what GTK does for its own CSD windows (e.g. with a GtkHeaderBar),
+// which is to draw the shadow inside a transparent margin and tell the compositor about it.
+
+use gtk::prelude::*;
+use gtk::{cairo, gdk};
+use tauri::{Runtime, Webview, WebviewWindow};
+
+use crate::windows;
+
+/// Width of the transparent margin the client-side shadow is drawn into.
+const SHADOW_EXTENT: i32 = 12;
+
+/// Width of the band along the window edges that stays clickable for resizing.
+/// Matches the border tao uses for undecorated windows.
+const RESIZE_BORDER: i32 = 5;
+
+/// Client-side frame settings for a window.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Frame {
+    shadow: i32,
+    radius: i32,
+    // maximized, fullscreen or tiled windows don't get a frame
+    tiled: bool,
+    connected: bool,
+}
+
+impl Frame {
+    fn shadow_extent(&self) -> i32 {
+        match self.tiled {
+            true => 0,
+            false => self.shadow,
+        }
+    }
+
+    fn corner_radius(&self) -> i32 {
+        match self.tiled {
+            true => 0,
+            false => self.radius,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.shadow_extent() > 0 || self.corner_radius() > 0
+    }
+}
+
+fn update_frame<R: Runtime>(win: &WebviewWindow<R>, f: impl FnOnce(&mut Frame)) -> Frame {
+    windows::update(win, win.label(), |window| {
+        let frame = window.frame.get_or_insert_with(Frame::default);
+        f(frame);
+        *frame
+    })
+    .unwrap_or_default()
+}
+
+/// Draw a shadow around the window contents and report it as frame extents,
+/// so compositors keep it out of snapping, tiling and maximizing.
+pub fn enable_csd_shadow<R: Runtime>(win: &WebviewWindow<R>) -> tauri::Result<()> {
+    set_frame(win, |frame| frame.shadow = SHADOW_EXTENT)
+}
+
+/// Round the corners of the window contents and let clicks outside of them through.
+pub fn set_corner_radius<R: Runtime>(win: &WebviewWindow<R>, radius: u32) -> tauri::Result<()> {
+    let radius = i32::try_from(radius).unwrap_or(i32::MAX);
+    set_frame(win, |frame| frame.radius = radius)
+}
+
+fn set_frame<R: Runtime>(win: &WebviewWindow<R>, f: impl FnOnce(&mut Frame)) -> tauri::Result<()> {
+    let gtk_win = win.gtk_window()?;
+
+    // Nothing is changed on windows that can't be made transparent
+    make_transparent(win)?;
+
+    let mut connect = false;
+    let frame = update_frame(win, |frame| {
+        connect = !frame.connected;
+        frame.connected = true;
+        f(frame);
+    });
+
+    if connect {
+        // Drop the frame whenever the window fills its monitor or a tile.
+        let win2 = win.clone();
+        gtk_win.connect_window_state_event(move |gtk_win, event| {
+            let tiled = event.new_window_state().intersects(
+                gdk::WindowState::MAXIMIZED
+                    | gdk::WindowState::FULLSCREEN
+                    | gdk::WindowState::TILED,
+            );
+            let frame = update_frame(&win2, |frame| frame.tiled = tiled);
+            apply_frame(gtk_win, &frame);
+            inject_frame(&win2, &frame);
+            gtk::glib::Propagation::Proceed
+        });
+
+        // Frame extents can only be set once the window has a GdkWindow.
+        let win2 = win.clone();
+        gtk_win.connect_realize(move |gtk_win| {
+            apply_frame(gtk_win, &update_frame(&win2, |_| {}));
+        });
+
+        // The input shape follows the size of the window.
+        let win2 = win.clone();
+        gtk_win.connect_size_allocate(move |gtk_win, _| {
+            apply_frame(gtk_win, &update_frame(&win2, |_| {}));
+        });
+    }
+
+    apply_frame(&gtk_win, &frame);
+    inject_frame(win, &frame);
+
+    Ok(())
+}
+
+/// Re-apply the frame styles after a page load.
+pub fn on_page_load<R: Runtime>(webview: &Webview<R>) {
+    let frame = windows::get(webview, webview.label(), |window| window.frame).flatten();
+
+    if let Some(frame) = frame {
+        webview
+            .eval(frame_script(&frame))
+            .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+    }
+}
+
+/// Give the window an RGBA visual and clear the webview background.
+/// Fails without changing anything when the window can't get an RGBA visual.
+pub fn make_transparent<R: Runtime>(win: &WebviewWindow<R>) -> tauri::Result<()> {
+    let gtk_win = win.gtk_window()?;
+    set_rgba_visual(&gtk_win)?;
+    gtk_win.set_app_paintable(true);
+
+    win.with_webview(|webview| {
+        use webkit2gtk::WebViewExt;
+        webview
+            .inner()
+            .set_background_color(&gdk::RGBA::new(0.0, 0.0, 0.0, 0.0));
+    })
+}
+
+/// The visual can only be set before the window is realized, which tao already does
+/// for windows created with `transparent: true`. Other windows can't be made transparent,
+/// as re-realizing them would lose their position and maximized state.
+fn set_rgba_visual(gtk_win: &gtk::ApplicationWindow) -> tauri::Result<()> {
+    let Some(visual) = WidgetExt::screen(gtk_win).and_then(|screen| screen.rgba_visual()) else {
+        // No compositing, the transparent parts would be drawn black.
+        return Err(
+            anyhow::anyhow!("transparent windows need a compositing window manager").into(),
+        );
+    };
+
+    if gtk_win.visual().as_ref() == Some(&visual) {
+        return Ok(());
+    }
+
+    if gtk_win.is_realized() {
+        return Err(anyhow::anyhow!(
+            "create the window with `transparent: true` to make it transparent on Linux"
+        )
+        .into());
+    }
+
+    gtk_win.set_visual(Some(&visual));
+    Ok(())
+}
+
+fn apply_frame(gtk_win: &gtk::ApplicationWindow, frame: &Frame) {
+    let extent = frame.shadow_extent();
+
+    if let Some(gdk_win) = gtk_win.window() {
+        // Sets _GTK_FRAME_EXTENTS on X11 and the window geometry on Wayland
+        gdk_win.set_shadow_width(extent, extent, extent, extent);
+    }
+
+    if !frame.is_active() {
+        gtk_win.input_shape_combine_region(None);
+        return;
+    }
+
+    let (width, height) = (gtk_win.allocated_width(), gtk_win.allocated_height());
+    let region = rounded_region(
+        extent,
+        extent,
+        width - extent * 2,
+        height - extent * 2,
+        frame.corner_radius(),
+    );
+
+    // Keep the outer edge of the shadow clickable so the window can still be resized.
+    if extent > 0 && gtk_win.is_resizable() {
+        let border = RESIZE_BORDER.min(extent);
+        for rect in [
+            cairo::RectangleInt::new(0, 0, width, border),
+            cairo::RectangleInt::new(0, height - border, width, border),
+            cairo::RectangleInt::new(0, 0, border, height),
+            cairo::RectangleInt::new(width - border, 0, border, height),
+        ] {
+            let _ = region.union_rectangle(&rect);
+        }
+    }
+
+    gtk_win.input_shape_combine_region(Some(&region));
+}
+
+/// Approximate a rounded rectangle with one row of pixels per line of the corners.
+fn rounded_region(x: i32, y: i32, width: i32, height: i32, radius: i32) -> cairo::Region {
+    let region = cairo::Region::create();
+    let (width, height) = (width.max(0), height.max(0));
+    let radius = radius.min(width / 2).min(height / 2);
+
+    let _ = region.union_rectangle(&cairo::RectangleInt::new(
+        x,
+        y + radius,
+        width,
+        height - radius * 2,
+    ));
+
+    for row in 0..radius {
+        let dy = (radius - row) as f64 - 0.5;
+        let r = radius as f64;
+        let inset = (r - (r * r - dy * dy).sqrt()).round() as i32;
+
+        for top in [y + row, y + height - row - 1] {
+            let _ = region.union_rectangle(&cairo::RectangleInt::new(
+                x + inset,
+                top,
+                width - inset * 2,
+                1,
+            ));
+        }
+    }
+
+    region
+}
+
+fn inject_frame<R: Runtime>(win: &WebviewWindow<R>, frame: &Frame) {
+    win.eval(frame_script(frame))
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
+
+fn frame_script(frame: &Frame) -> String {
+    include_str!("js/frame.js")
+        .replace("@frame-shadow", &frame.shadow_extent().to_string())
+        .replace("@frame-radius", &frame.corner_radius().to_string())
+}
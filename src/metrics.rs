@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Runtime, WebviewWindow};
+
+use crate::windows;
+
+/// A rectangle in logical pixels, relative to the top left of the webview.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Where the titlebar and the window controls drawn by decorum are.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TitlebarMetrics {
+    /// The `[data-tauri-decorum-tb]` element, `None` when it isn't on the page.
+    pub titlebar: Option<Bounds>,
+    /// The controls and custom buttons, keyed by id, e.g. `minimize` or `settings`.
+    pub controls: HashMap<String, Bounds>,
+}
+
+/// Store the metrics reported by the page and emit them as a `decorum://metrics` event.
+pub fn report<R: Runtime>(win: &WebviewWindow<R>, metrics: TitlebarMetrics) {
+    let stored = windows::update(win, win.label(), |window| {
+        window.metrics = Some(metrics.clone())
+    });
+
+    if stored.is_none() {
+        return;
+    }
+
+    win.emit_to(win.label(), "decorum://metrics", metrics)
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
+
+/// The metrics last reported by the window.
+pub fn get<R: Runtime>(win: &WebviewWindow<R>) -> Option<TitlebarMetrics> {
+    windows::get(win, win.label(), |window| window.metrics.clone()).flatten()
+}
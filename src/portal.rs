@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use gtk::gio::{self, prelude::*};
+use gtk::glib::{Variant, VariantTy};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::windows;
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const PORTAL_SETTINGS: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE: &str = "org.freedesktop.appearance";
+
+/// Milliseconds to wait for the portal, so a broken one can't hold up the app.
+const READ_TIMEOUT: i32 = 2000;
+
+/// The value inside of a variant, which older portals box twice, e.g. `<<uint32 1>>`.
+fn unboxed(value: &Variant) -> Variant {
+    match value.is_type(VariantTy::VARIANT) {
+        true => value
+            .as_variant()
+            .map_or_else(|| value.clone(), |inner| unboxed(&inner)),
+        false => value.clone(),
+    }
+}
+
+fn color_scheme(value: &Variant) -> Option<&'static str> {
+    unboxed(value).get::<u32>().map(|value| match value {
+        1 => "dark",
+        2 => "light",
+        _ => "normal",
+    })
+}
+
+/// Values outside of 0..1 mean the user has no accent color set
+fn accent_color(value: &Variant) -> Option<String> {
+    let (r, g, b) = unboxed(value).get::<(f64, f64, f64)>()?;
+
+    [r, g, b].iter().all(|c| (0.0..=1.0).contains(c)).then(|| {
+        format!(
+            "rgb({}, {}, {})",
+            (r * 255.0).round(),
+            (g * 255.0).round(),
+            (b * 255.0).round()
+        )
+    })
+}
+
+fn contrast(value: &Variant) -> Option<&'static str> {
+    unboxed(value).get::<u32>().map(|value| match value {
+        1 => "high",
+        _ => "normal",
+    })
+}
+
+/// User preferences from the `org.freedesktop.appearance` namespace, as CSS values.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Appearance {
+    color_scheme: Option<&'static str>,
+    accent_color: Option<String>,
+    contrast: Option<&'static str>,
+}
+
+impl Appearance {
+    /// The settings returned by `ReadAll`, a `(a{sa{sv}})` of namespaces and their settings.
+    fn from_settings(settings: &Variant) -> Self {
+        let mut appearance = Self::default();
+
+        let namespaces = settings.get::<(HashMap<String, HashMap<String, Variant>>,)>();
+        if let Some(values) = namespaces.and_then(|(mut namespaces,)| namespaces.remove(APPEARANCE))
+        {
+            for (key, value) in values {
+                appearance.set(&key, &value);
+            }
+        }
+
+        appearance
+    }
+
+    fn set(&mut self, key: &str, value: &Variant) {
+        match key {
+            "color-scheme" => self.color_scheme = color_scheme(value),
+            "accent-color" => self.accent_color = accent_color(value),
+            "contrast" => self.contrast = contrast(value),
+            _ => {}
+        }
+    }
+
+    fn script(&self) -> String {
+        let vars = [
+            ("--decorum-accent", self.accent_color.as_deref()),
+            ("--decorum-color-scheme", self.color_scheme),
+            ("--decorum-contrast", self.contrast),
+        ];
+
+        vars.iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!(
+                    "document.documentElement.style.setProperty('{}', '{}');",
+                    name, value
+                ),
+                None => format!("document.documentElement.style.removeProperty('{}');", name),
+            })
+            .collect()
+    }
+}
+
+/// Latest appearance read from the portal.
+#[derive(Default)]
+pub struct AppearanceState {
+    appearance: Mutex<Option<Appearance>>,
+    // kept alive to keep receiving SettingChanged
+    proxy: Mutex<Option<gio::DBusProxy>>,
+}
+
+/// Store the appearance and publish it to every window with an overlay titlebar.
+fn update<R: Runtime>(app: &AppHandle<R>, f: impl FnOnce(&mut Appearance)) {
+    let state = app.state::<AppearanceState>();
+
+    let appearance = {
+        let mut appearance = state.appearance.lock().unwrap();
+        let appearance = appearance.get_or_insert_with(Appearance::default);
+        f(appearance);
+        appearance.clone()
+    };
+
+    for label in windows::decorated(app) {
+        if let Some(win) = app.get_webview_window(&label) {
+            win.eval(appearance.script())
+                .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+        }
+    }
+}
+
+/// Read the appearance settings from the portal and follow their changes.
+/// Everything runs asynchronously on the main loop, so a slow portal doesn't hold up the app.
+pub fn watch<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+
+    gio::DBusProxy::for_bus(
+        gio::BusType::Session,
+        gio::DBusProxyFlags::DO_NOT_LOAD_PROPERTIES,
+        None,
+        PORTAL_DEST,
+        PORTAL_PATH,
+        PORTAL_SETTINGS,
+        None::<&gio::Cancellable>,
+        move |proxy| {
+            let proxy = match proxy {
+                Ok(proxy) => proxy,
+                Err(e) => return println!("decorum error: {:?}", e),
+            };
+
+            // SettingChanged (namespace, key, value) carries the new value
+            let app2 = app.clone();
+            proxy.connect_local("g-signal", false, move |args| {
+                let signal = args.get(2).and_then(|arg| arg.get::<String>().ok());
+                let params = args.get(3).and_then(|arg| arg.get::<Variant>().ok());
+
+                if let (Some("SettingChanged"), Some(params)) = (signal.as_deref(), params) {
+                    if let Some((namespace, key, value)) = params.get::<(String, String, Variant)>()
+                    {
+                        if namespace == APPEARANCE {
+                            update(&app2, |appearance| appearance.set(&key, &value));
+                        }
+                    }
+                }
+
+                None
+            });
+
+            let app2 = app.clone();
+            proxy.call(
+                "ReadAll",
+                Some(&(vec![APPEARANCE],).to_variant()),
+                gio::DBusCallFlags::NONE,
+                READ_TIMEOUT,
+                None::<&gio::Cancellable>,
+                move |settings| match settings {
+                    Ok(settings) => {
+                        let read = Appearance::from_settings(&settings);
+                        update(&app2, |appearance| *appearance = read);
+                    }
+                    Err(e) => println!("decorum error: {:?}", e),
+                },
+            );
+
+            *app.state::<AppearanceState>().proxy.lock().unwrap() = Some(proxy);
+        },
+    );
+}
+
+/// Publish the appearance settings into the window as CSS variables,
+/// and keep them updated when they change.
+/// Windows loaded before the portal answers get the settings once they're read.
+pub fn publish<R: Runtime>(win: &WebviewWindow<R>) {
+    let Some(state) = win.try_state::<AppearanceState>() else {
+        return;
+    };
+
+    let appearance = state.appearance.lock().unwrap().clone();
+
+    if let Some(appearance) = appearance {
+        win.eval(appearance.script())
+            .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_color_scheme_and_contrast() {
+        assert_eq!(color_scheme(&1u32.to_variant()), Some("dark"));
+        assert_eq!(color_scheme(&2u32.to_variant()), Some("light"));
+        assert_eq!(color_scheme(&0u32.to_variant()), Some("normal"));
+        assert_eq!(contrast(&1u32.to_variant()), Some("high"));
+        assert_eq!(contrast(&0u32.to_variant()), Some("normal"));
+
+        // Boxed twice by older portals
+        let boxed = Variant::from_variant(&Variant::from_variant(&1u32.to_variant()));
+        assert_eq!(color_scheme(&boxed), Some("dark"));
+
+        assert_eq!(color_scheme(&"dark".to_variant()), None);
+    }
+
+    #[test]
+    fn reads_accent_color() {
+        assert_eq!(
+            accent_color(&(0.2, 0.5, 1.0).to_variant()).as_deref(),
+            Some("rgb(51, 128, 255)")
+        );
+        // No accent color set
+        assert_eq!(accent_color(&(-1.0, -1.0, -1.0).to_variant()), None);
+        assert_eq!(accent_color(&(0.2, 0.5).to_variant()), None);
+        assert_eq!(accent_color(&1u32.to_variant()), None);
+    }
+
+    #[test]
+    fn reads_all_settings() {
+        let settings = HashMap::from([
+            ("color-scheme".to_string(), 1u32.to_variant()),
+            ("accent-color".to_string(), (0.2, 0.5, 1.0).to_variant()),
+            ("reduced-motion".to_string(), 1u32.to_variant()),
+        ]);
+        let namespaces = HashMap::from([(APPEARANCE.to_string(), settings)]);
+
+        assert_eq!(
+            Appearance::from_settings(&(namespaces,).to_variant()),
+            Appearance {
+                color_scheme: Some("dark"),
+                accent_color: Some("rgb(51, 128, 255)".to_string()),
+                contrast: None,
+            }
+        );
+
+        assert_eq!(
+            Appearance::from_settings(&"garbage".to_variant()),
+            Appearance::default()
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::async_runtime::{channel, Sender};
+use tauri::{Manager, Runtime, Webview, WebviewWindow};
+
+use crate::windows;
+
+/// How long a window waits for its titlebar before it's shown anyway, with `show_when_ready`.
+const SHOW_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long the page has to report the titlebar before it's given up on, e.g. when it can't
+/// call the plugin. Longer than the 10 seconds the page waits for a mount point itself.
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Whether the titlebar made it onto the page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TitlebarStatus {
+    /// The titlebar and the window controls are on the page.
+    Mounted,
+    /// The titlebar or the controls could not be mounted, with the reason reported by the page.
+    Failed(String),
+}
+
+impl TitlebarStatus {
+    fn timed_out() -> Self {
+        Self::Failed(format!(
+            "The titlebar was not reported within {} seconds, check that the window has an overlay \
+             titlebar, `withGlobalTauri` is on and the decorum permissions are granted",
+            READY_TIMEOUT.as_secs()
+        ))
+    }
+}
+
+type OnReady<R> = Arc<dyn Fn(&WebviewWindow<R>, &TitlebarStatus) + Send + Sync>;
+
+/// Progress of the titlebar on the current page of a window.
+pub struct WindowReady<R: Runtime> {
+    /// Counts the page loads, so a deadline only applies to the page it was set for.
+    page: u64,
+    /// Parts of the titlebar still to be reported by the page, e.g. `titlebar` and `controls`.
+    pending: HashSet<String>,
+    status: Option<TitlebarStatus>,
+    callbacks: Vec<OnReady<R>>,
+    waiters: Vec<Sender<TitlebarStatus>>,
+}
+
+impl<R: Runtime> Default for WindowReady<R> {
+    fn default() -> Self {
+        Self {
+            page: 0,
+            pending: HashSet::new(),
+            status: None,
+            callbacks: Vec::new(),
+            waiters: Vec::new(),
+        }
+    }
+}
+
+/// Start waiting for the parts of the titlebar to be reported by a newly loaded page.
+/// Gives up after [`READY_TIMEOUT`], in case the page never reports them.
+pub fn expect<R: Runtime>(win: &Webview<R>, parts: &[&str]) {
+    let page = windows::update(win, win.label(), |window| {
+        window.ready.page += 1;
+        window.ready.pending = parts.iter().map(|part| part.to_string()).collect();
+        window.ready.status = None;
+        window.ready.page
+    });
+
+    let Some(page) = page else {
+        return;
+    };
+
+    let (app, label) = (win.app_handle().clone(), win.label().to_string());
+    std::thread::spawn(move || {
+        std::thread::sleep(READY_TIMEOUT);
+
+        if let Some(win) = app.get_webview_window(&label) {
+            resolve(&win, |window| {
+                (window.page == page).then(TitlebarStatus::timed_out)
+            });
+        }
+    });
+}
+
+/// A part of the titlebar was mounted, or failed to. Callbacks run once all parts are
+/// mounted or as soon as one fails.
+pub fn report<R: Runtime>(win: &WebviewWindow<R>, part: &str, error: Option<String>) {
+    resolve(win, |window| {
+        // Controls rendered again later don't count
+        if !window.pending.remove(part) {
+            return None;
+        }
+
+        match error {
+            Some(error) => Some(TitlebarStatus::Failed(error)),
+            None if window.pending.is_empty() => Some(TitlebarStatus::Mounted),
+            None => None,
+        }
+    });
+}
+
+/// Settle the status of the current page with the one returned by `f`, unless it already is,
+/// and pass it to the callbacks and waiters.
+fn resolve<R: Runtime>(
+    win: &WebviewWindow<R>,
+    f: impl FnOnce(&mut WindowReady<R>) -> Option<TitlebarStatus>,
+) {
+    let resolved = windows::update(win, win.label(), |window| {
+        let window = &mut window.ready;
+
+        if window.status.is_some() {
+            return None;
+        }
+
+        let status = f(window)?;
+
+        window.status = Some(status.clone());
+        for waiter in window.waiters.drain(..) {
+            let _ = waiter.try_send(status.clone());
+        }
+
+        Some((status, window.callbacks.clone()))
+    });
+
+    let Some((status, callbacks)) = resolved.flatten() else {
+        return;
+    };
+
+    if let TitlebarStatus::Failed(error) = &status {
+        println!("decorum error: {}", error);
+    }
+
+    // The callbacks may register more callbacks, so they run without holding the lock
+    for callback in callbacks {
+        callback(win, &status);
+    }
+}
+
+/// Run `callback` whenever the titlebar is mounted or fails to, and right away if it already was.
+pub fn on_ready<R: Runtime, F>(win: &WebviewWindow<R>, callback: F)
+where
+    F: Fn(&WebviewWindow<R>, &TitlebarStatus) + Send + Sync + 'static,
+{
+    let callback: OnReady<R> = Arc::new(callback);
+
+    let status = windows::update(win, win.label(), |window| {
+        window.ready.callbacks.push(callback.clone());
+        window.ready.status.clone()
+    });
+
+    if let Some(status) = status.flatten() {
+        callback(win, &status);
+    }
+}
+
+/// Resolves once the titlebar is mounted or fails to, right away if it already was,
+/// and after [`READY_TIMEOUT`] at most.
+pub fn wait<R: Runtime>(
+    win: &WebviewWindow<R>,
+) -> Pin<Box<dyn Future<Output = TitlebarStatus> + Send>> {
+    let (sender, mut receiver) = channel(1);
+
+    let timeout = sender.clone();
+    let registered = windows::update(win, win.label(), |window| match &window.ready.status {
+        Some(status) => {
+            let _ = sender.try_send(status.clone());
+            false
+        }
+        None => {
+            window.ready.waiters.push(sender);
+            true
+        }
+    });
+
+    match registered {
+        None => {
+            let status =
+                TitlebarStatus::Failed("The decorum plugin is not initialized".to_string());
+            return Box::pin(async move { status });
+        }
+        // Also given up on for windows that never load a page with an overlay titlebar
+        Some(true) => {
+            std::thread::spawn(move || {
+                std::thread::sleep(READY_TIMEOUT);
+                let _ = timeout.try_send(TitlebarStatus::timed_out());
+            });
+        }
+        Some(false) => {}
+    }
+
+    Box::pin(async move {
+        receiver
+            .recv()
+            .await
+            .unwrap_or_else(|| TitlebarStatus::Failed("The window was destroyed".to_string()))
+    })
+}
+
+/// Keep the window hidden until the titlebar is mounted, or for [`SHOW_TIMEOUT`] at most.
+pub fn show_when_ready<R: Runtime>(win: &WebviewWindow<R>) -> tauri::Result<()> {
+    win.hide()?;
+
+    let shown = Arc::new(AtomicBool::new(false));
+    let show = move |win: &WebviewWindow<R>| {
+        if !shown.swap(true, Ordering::SeqCst) {
+            // The window may have been destroyed in the meantime
+            let _ = win.show();
+        }
+    };
+
+    let (win2, show2) = (win.clone(), show.clone());
+    std::thread::spawn(move || {
+        std::thread::sleep(SHOW_TIMEOUT);
+        show2(&win2);
+    });
+
+    let (win2, ready) = (win.clone(), wait(win));
+    tauri::async_runtime::spawn(async move {
+        ready.await;
+        show(&win2);
+    });
+
+    Ok(())
+}
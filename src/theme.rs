@@ -0,0 +1,117 @@
+use std::sync::Mutex;
+
+use tauri::window::Color;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::windows;
+
+/// Colors of the titlebar and its window controls.
+///
+/// Colors left as `None` fall back to the defaults for the platform and the current light/dark theme.
+/// Each color is set on the page as a CSS variable, noted below, which can also be used by your own styles.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecorumTheme {
+    /// Color of the control icons. `--decorum-tb-fg`
+    pub foreground: Option<Color>,
+    /// Background of the titlebar. `--decorum-tb-bg`
+    pub background: Option<Color>,
+    /// Background of a hovered control. `--decorum-tb-hover-bg`
+    pub hover: Option<Color>,
+    /// Background of a pressed control. `--decorum-tb-pressed-bg`
+    pub pressed: Option<Color>,
+    /// Background of the hovered close button. `--decorum-tb-close-hover-bg`
+    pub close_hover: Option<Color>,
+    /// Color of the control icons while the window is not focused. `--decorum-tb-inactive-fg`
+    pub inactive: Option<Color>,
+    /// Background of the titlebar while the window is not focused. `--decorum-tb-inactive-bg`
+    pub inactive_background: Option<Color>,
+}
+
+impl DecorumTheme {
+    /// This theme with the colors set in `other` taking precedence.
+    fn merge(&self, other: &DecorumTheme) -> DecorumTheme {
+        DecorumTheme {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            hover: other.hover.or(self.hover),
+            pressed: other.pressed.or(self.pressed),
+            close_hover: other.close_hover.or(self.close_hover),
+            inactive: other.inactive.or(self.inactive),
+            inactive_background: other.inactive_background.or(self.inactive_background),
+        }
+    }
+
+    fn script(&self) -> String {
+        let vars = [
+            ("--decorum-tb-fg", self.foreground),
+            ("--decorum-tb-bg", self.background),
+            ("--decorum-tb-hover-bg", self.hover),
+            ("--decorum-tb-pressed-bg", self.pressed),
+            ("--decorum-tb-close-hover-bg", self.close_hover),
+            ("--decorum-tb-inactive-fg", self.inactive),
+            ("--decorum-tb-inactive-bg", self.inactive_background),
+        ];
+
+        vars.iter()
+            .map(|(name, color)| match color {
+                Some(Color(r, g, b, a)) => format!(
+                    "document.documentElement.style.setProperty('{}', 'rgba({}, {}, {}, {})');",
+                    name,
+                    r,
+                    g,
+                    b,
+                    *a as f32 / 255.0
+                ),
+                None => format!("document.documentElement.style.removeProperty('{}');", name),
+            })
+            .collect()
+    }
+}
+
+/// The app-wide titlebar theme.
+#[derive(Default)]
+pub struct Themes {
+    app: Mutex<Option<DecorumTheme>>,
+}
+
+/// The app-wide theme, overridden by the theme set on the window.
+fn resolve<R: Runtime>(win: &WebviewWindow<R>) -> Option<DecorumTheme> {
+    let app = win.try_state::<Themes>()?.app.lock().unwrap().clone();
+    let window = windows::get(win, win.label(), |window| window.theme.clone()).flatten();
+
+    match (app, window) {
+        (Some(app), Some(window)) => Some(app.merge(&window)),
+        (app, window) => app.or(window),
+    }
+}
+
+/// Set the titlebar theme of a window and apply it to the page.
+pub fn set<R: Runtime>(win: &WebviewWindow<R>, theme: DecorumTheme) {
+    windows::update(win, win.label(), |window| window.theme = Some(theme));
+
+    apply(win);
+}
+
+/// Set the titlebar theme of all windows, including ones decorated later.
+/// Themes set on individual windows take precedence over it.
+pub fn set_app<R: Runtime>(app: &AppHandle<R>, theme: DecorumTheme) {
+    let Some(themes) = app.try_state::<Themes>() else {
+        return;
+    };
+
+    *themes.app.lock().unwrap() = Some(theme);
+
+    for label in windows::decorated(app) {
+        if let Some(win) = app.get_webview_window(&label) {
+            apply(&win);
+        }
+    }
+}
+
+/// Apply the titlebar theme of a window to the page, e.g. after a page load.
+pub fn apply<R: Runtime>(win: &WebviewWindow<R>) {
+    if let Some(theme) = resolve(win) {
+        win.eval(theme.script())
+            .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+    }
+}
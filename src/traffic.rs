@@ -86,6 +86,62 @@ struct WindowState<R: Runtime> {
     traffic_light_y: f64,
 }
 
+/// Hide the native traffic lights, when custom controls are drawn in their place.
+#[cfg(target_os = "macos")]
+pub fn hide_traffic_lights(ns_window_handle: UnsafeWindowHandle) {
+    use cocoa::appkit::{NSWindow, NSWindowButton};
+    use cocoa::base::YES;
+    let ns_window = ns_window_handle.0 as cocoa::base::id;
+    unsafe {
+        for kind in [
+            NSWindowButton::NSWindowCloseButton,
+            NSWindowButton::NSWindowMiniaturizeButton,
+            NSWindowButton::NSWindowZoomButton,
+        ] {
+            let button = ns_window.standardWindowButton_(kind);
+            if !button.is_null() {
+                let _: () = msg_send![button, setHidden: YES];
+            }
+        }
+    }
+}
+
+/// Space taken by the visible traffic lights, from the left edge of the window,
+/// and the height of the titlebar they sit in, in points.
+#[cfg(target_os = "macos")]
+pub fn traffic_lights_geometry(ns_window_handle: UnsafeWindowHandle) -> Option<(f64, f64)> {
+    use cocoa::appkit::{NSView, NSWindow, NSWindowButton};
+    use cocoa::base::{BOOL, NO};
+    use cocoa::foundation::NSRect;
+    let ns_window = ns_window_handle.0 as cocoa::base::id;
+    unsafe {
+        let close = ns_window.standardWindowButton_(NSWindowButton::NSWindowCloseButton);
+        let zoom = ns_window.standardWindowButton_(NSWindowButton::NSWindowZoomButton);
+        if close.is_null() || zoom.is_null() {
+            return None;
+        }
+
+        let hidden: BOOL = msg_send![close, isHidden];
+        if hidden != NO {
+            return None;
+        }
+
+        let close_superview = close.superview();
+        if close_superview.is_null() || close_superview.superview().is_null() {
+            return None;
+        }
+
+        let close_rect: NSRect = msg_send![close, frame];
+        let zoom_rect: NSRect = msg_send![zoom, frame];
+        let title_bar_rect = NSView::frame(close_superview.superview());
+
+        // Leave the same margin on the right of the buttons as on their left
+        let width = zoom_rect.origin.x + zoom_rect.size.width + close_rect.origin.x;
+
+        Some((width, title_bar_rect.size.height))
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub fn setup_traffic_light_positioner<R: Runtime>(window: Window<R>) {
     use cocoa::appkit::{NSWindow, NSWindowButton};
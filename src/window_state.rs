@@ -0,0 +1,81 @@
+use serde::Serialize;
+use tauri::{Emitter, Runtime, WebviewWindow};
+
+use crate::windows;
+
+/// State of a window, pushed to the page as `data-decorum-*` attributes
+/// and a `decorum://window-state` event whenever it changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WindowState {
+    pub maximized: bool,
+    pub minimized: bool,
+    pub fullscreen: bool,
+    pub focused: bool,
+}
+
+impl WindowState {
+    fn read<R: Runtime>(win: &WebviewWindow<R>) -> Self {
+        Self {
+            maximized: win.is_maximized().unwrap_or(false),
+            minimized: win.is_minimized().unwrap_or(false),
+            fullscreen: win.is_fullscreen().unwrap_or(false),
+            focused: win.is_focused().unwrap_or(false),
+        }
+    }
+
+    fn script(&self) -> String {
+        let state = serde_json::to_string(self).unwrap_or_default();
+
+        include_str!("js/state.js").replacen("\"@decorum-state\"", &state, 1)
+    }
+}
+
+/// Read the state of the window and push it to the page if it changed.
+/// `focused` is taken from the focus event, as the window may not report it yet.
+/// Returns the state the page had before when it changed.
+pub fn update<R: Runtime>(win: &WebviewWindow<R>, focused: Option<bool>) -> Option<WindowState> {
+    let mut state = WindowState::read(win);
+    if let Some(focused) = focused {
+        state.focused = focused;
+    }
+
+    let previous = windows::update(win, win.label(), |window| window.state.replace(state))?;
+
+    if previous == Some(state) {
+        return None;
+    }
+
+    win.eval(state.script())
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+
+    win.emit_to(win.label(), "decorum://window-state", state)
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+
+    previous
+}
+
+/// Push the state of the window to the page, e.g. after a page load.
+pub fn apply<R: Runtime>(win: &WebviewWindow<R>) {
+    let mut state = WindowState::read(win);
+    let updated = windows::update(win, win.label(), |window| {
+        // The focus follows the focus events, see `update`
+        if let Some(previous) = window.state {
+            state.focused = previous.focused;
+        }
+        window.state = Some(state);
+    });
+
+    if updated.is_none() {
+        return;
+    }
+
+    win.eval(state.script())
+        .unwrap_or_else(|e| println!("decorum error: {:?}", e));
+}
+
+/// The state last pushed to the page, or the current one if none was.
+pub fn get<R: Runtime>(win: &WebviewWindow<R>) -> WindowState {
+    let state = windows::get(win, win.label(), |window| window.state).flatten();
+
+    state.unwrap_or_else(|| WindowState::read(win))
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::actions::Control;
+use crate::control_state::ControlState;
+use crate::metrics::TitlebarMetrics;
+use crate::ready::WindowReady;
+use crate::theme::DecorumTheme;
+use crate::window_state::WindowState;
+
+/// Everything decorum keeps about a window.
+pub struct WindowData<R: Runtime> {
+    /// Whether the window has an overlay titlebar.
+    pub decorated: bool,
+    /// The titlebar theme set on the window.
+    pub theme: Option<DecorumTheme>,
+    /// The control states set on the window.
+    pub controls: HashMap<Control, ControlState>,
+    /// The control states last rendered on the page.
+    pub rendered: Option<Vec<(Control, ControlState)>>,
+    /// The state last pushed to the page.
+    pub state: Option<WindowState>,
+    /// The metrics last reported by the page.
+    pub metrics: Option<TitlebarMetrics>,
+    /// Progress of the titlebar on the current page.
+    pub ready: WindowReady<R>,
+    /// The client-side frame of the window.
+    #[cfg(target_os = "linux")]
+    pub frame: Option<crate::linux::Frame>,
+}
+
+impl<R: Runtime> Default for WindowData<R> {
+    fn default() -> Self {
+        Self {
+            decorated: false,
+            theme: None,
+            controls: HashMap::new(),
+            rendered: None,
+            state: None,
+            metrics: None,
+            ready: WindowReady::default(),
+            #[cfg(target_os = "linux")]
+            frame: None,
+        }
+    }
+}
+
+/// The data of each window, keyed by window label.
+pub struct Windows<R: Runtime> {
+    windows: Mutex<HashMap<String, WindowData<R>>>,
+}
+
+impl<R: Runtime> Default for Windows<R> {
+    fn default() -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Read the data of a window, `None` when there is none.
+/// `f` runs with the data locked, so it must not call back into this module.
+pub fn get<R: Runtime, T>(
+    manager: &impl Manager<R>,
+    label: &str,
+    f: impl FnOnce(&WindowData<R>) -> T,
+) -> Option<T> {
+    let windows = manager.try_state::<Windows<R>>()?;
+    let windows = windows.windows.lock().unwrap();
+
+    windows.get(label).map(f)
+}
+
+/// Change the data of a window, starting from the defaults if there is none yet.
+/// `f` runs with the data locked, so it must not call back into this module.
+pub fn update<R: Runtime, T>(
+    manager: &impl Manager<R>,
+    label: &str,
+    f: impl FnOnce(&mut WindowData<R>) -> T,
+) -> Option<T> {
+    let windows = manager.try_state::<Windows<R>>()?;
+    let mut windows = windows.windows.lock().unwrap();
+
+    Some(f(windows.entry(label.to_string()).or_default()))
+}
+
+/// Mark the window as having an overlay titlebar.
+pub fn register<R: Runtime>(win: &WebviewWindow<R>) {
+    update(win, win.label(), |window| window.decorated = true);
+}
+
+/// Whether the window has an overlay titlebar.
+pub fn is_decorated<R: Runtime>(manager: &impl Manager<R>, label: &str) -> bool {
+    get(manager, label, |window| window.decorated).unwrap_or(false)
+}
+
+/// Labels of the windows with an overlay titlebar.
+pub fn decorated<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let Some(windows) = app.try_state::<Windows<R>>() else {
+        return Vec::new();
+    };
+
+    let windows = windows.windows.lock().unwrap();
+    windows
+        .iter()
+        .filter(|(_, window)| window.decorated)
+        .map(|(label, _)| label.clone())
+        .collect()
+}
+
+/// Forget about a window once it's destroyed, so a new window with the same label starts over.
+pub fn unregister<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    // Dropped after the lock is released, e.g. the senders of `wait`
+    let window = app
+        .try_state::<Windows<R>>()
+        .and_then(|windows| windows.windows.lock().unwrap().remove(label));
+    drop(window);
+
+    crate::buttons::remove_all(app, label);
+}
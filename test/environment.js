@@ -0,0 +1,151 @@
+// A page in jsdom to evaluate the injected scripts in, with the parts of the webview they
+// use that jsdom doesn't have stubbed out, and what they leave behind kept track of.
+import { JSDOM, VirtualConsole } from "jsdom";
+
+export const createEnvironment = async () => {
+	const errors = [];
+	const virtualConsole = new VirtualConsole();
+	virtualConsole.on("jsdomError", (error) => errors.push(error));
+
+	const dom = new JSDOM("<!doctype html><html><head></head><body></body></html>", {
+		runScripts: "outside-only",
+		pretendToBeVisual: true,
+		virtualConsole,
+	});
+	const { window } = dom;
+	const { document } = window;
+
+	// Scripts are evaluated into a page that finished loading, readyState is set by the tests
+	if (document.readyState !== "complete") {
+		await new Promise((resolve) => window.addEventListener("load", resolve, { once: true }));
+	}
+
+	let readyState = "complete";
+	Object.defineProperty(document, "readyState", {
+		configurable: true,
+		get: () => readyState,
+		set: (state) => (readyState = state),
+	});
+
+	// DOMContentLoaded listeners that haven't run yet
+	const loadListeners = new Set();
+	const addEventListener = document.addEventListener.bind(document);
+	document.addEventListener = (type, listener, options) => {
+		if (type !== "DOMContentLoaded") return addEventListener(type, listener, options);
+
+		loadListeners.add(listener);
+		addEventListener(
+			type,
+			(event) => {
+				loadListeners.delete(listener);
+				listener.call(document, event);
+			},
+			options,
+		);
+	};
+
+	// Observers that are observing something
+	const observers = new Set();
+	window.MutationObserver = class extends window.MutationObserver {
+		observe(target, options) {
+			observers.add(this);
+			super.observe(target, options);
+		}
+
+		disconnect() {
+			observers.delete(this);
+			super.disconnect();
+		}
+	};
+
+	window.ResizeObserver = class {
+		observe() {}
+		unobserve() {}
+		disconnect() {}
+	};
+
+	// Timers are kept track of but never fire, e.g. the 10 second mount timeout
+	const timers = new Map();
+	let nextTimer = 1;
+	window.setTimeout = (callback, delay) => {
+		const id = nextTimer++;
+		timers.set(id, { callback, delay });
+		return id;
+	};
+	window.clearTimeout = (id) => timers.delete(id);
+
+	// Media queries match up to the width of the window
+	window.matchMedia = (query) => {
+		const max = /max-width:\s*(\d+)px/.exec(query);
+		return { media: query, matches: !max || window.innerWidth <= Number(max[1]) };
+	};
+
+	window.fetch = () => Promise.reject(new Error("no network"));
+
+	const invokes = [];
+	window.__TAURI__ = {
+		core: {
+			invoke(command, args) {
+				invokes.push({ command, args });
+				return Promise.resolve(null);
+			},
+		},
+		window: {
+			getCurrentWindow: () => ({
+				setFocus: () => Promise.resolve(),
+				startDragging() {
+					invokes.push({ command: "startDragging" });
+					return Promise.resolve();
+				},
+			}),
+		},
+	};
+
+	return {
+		window,
+		document,
+		errors,
+		observers,
+		timers,
+		invokes,
+		loadListeners,
+
+		// Evaluate one of the injected scripts, as the rust side does with `eval`
+		run(source) {
+			window.eval(source);
+		},
+
+		// The page finishes parsing, as it does after a script injected early ran
+		finishLoading() {
+			readyState = "interactive";
+			document.dispatchEvent(new window.Event("DOMContentLoaded"));
+			readyState = "complete";
+		},
+
+		// Press the mouse on an element, inside a shadow root or not. Returns what listeners
+		// on the document saw: the path of the event and its target, retargeted to the
+		// outermost shadow host.
+		press(el, init = {}) {
+			let seen;
+			const see = (event) => (seen = { path: event.composedPath(), target: event.target });
+			document.addEventListener("mousedown", see, { capture: true, once: true });
+
+			const event = new window.MouseEvent("mousedown", {
+				bubbles: true,
+				cancelable: true,
+				composed: true,
+				button: 0,
+				detail: 1,
+				...init,
+			});
+			el.dispatchEvent(event);
+
+			return { ...seen, defaultPrevented: event.defaultPrevented };
+		},
+
+		// Let the mutation observers run
+		async settle() {
+			for (let i = 0; i < 5; i++) await new Promise((resolve) => setImmediate(resolve));
+		},
+	};
+};
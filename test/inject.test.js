@@ -0,0 +1,295 @@
+// The injected scripts are eval'd before or after the page is parsed, depending on when the
+// page load is reported, and must end up with the same page either way.
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { describe, it } from "node:test";
+
+import { createEnvironment } from "./environment.js";
+
+const script = (name) => readFileSync(new URL(`../src/js/${name}`, import.meta.url), "utf8");
+
+const elements = script("elements.js");
+
+const titlebar = (options = {}) =>
+	script("titlebar.js").replace(
+		'"@decorum-titlebar"',
+		JSON.stringify({ mount: null, headless: false, ...options }),
+	);
+
+const controls = (config = {}) =>
+	script("controls.js").replace(
+		'"@decorum-config"',
+		JSON.stringify({
+			style: "windows",
+			platform: "windows",
+			side: "right",
+			controls: ["minimize", "maximize", "close"],
+			disabled: [],
+			buttons: [],
+			icons: {},
+			mount: null,
+			...config,
+		}),
+	);
+
+// Evaluate the scripts the way create_overlay_titlebar does, with the page still loading or not
+const load = async (readyState, { page, options, config } = {}) => {
+	const env = await createEnvironment();
+
+	// The elements are registered on every page before it's parsed, see Builder::build
+	env.run(elements);
+
+	env.document.readyState = readyState;
+	page?.(env.document);
+
+	env.run(titlebar(options));
+
+	// In headless mode the page renders the controls, see controls::script
+	if (!options?.headless) env.run(controls(config));
+
+	if (readyState === "loading") env.finishLoading();
+	await env.settle();
+
+	return env;
+};
+
+const mountedParts = (env) =>
+	env.invokes
+		.filter(({ command }) => command === "plugin:decorum|titlebar_mounted")
+		.map(({ args }) => [args.part, args.error]);
+
+const assertSettled = (env) => {
+	// Nothing is left waiting on the page: the 10 second mount timeout is cleared
+	// and the DOMContentLoaded listeners ran
+	assert.equal(env.timers.size, 0);
+	assert.equal(env.loadListeners.size, 0);
+	assert.deepEqual(env.errors, []);
+
+	// The controls follow the titlebar as it's re-rendered, the app-region rules follow the
+	// stylesheets, and each <decorum-controls> mirrors the window state while it's on the page.
+	// Nothing else keeps observing.
+	const connected = env.document.querySelectorAll("decorum-controls").length;
+	assert.equal(env.observers.size, 2 + connected);
+};
+
+for (const readyState of ["loading", "complete"]) {
+	describe(`scripts injected with readyState ${readyState}`, () => {
+		it("creates one titlebar with one set of controls", async () => {
+			const env = await load(readyState);
+
+			const bars = env.document.querySelectorAll("[data-tauri-decorum-tb]");
+			const sets = env.document.querySelectorAll("decorum-controls");
+			assert.equal(bars.length, 1);
+			assert.equal(sets.length, 1);
+			assert.equal(sets[0].parentNode, bars[0]);
+
+			const buttons = sets[0].shadowRoot.querySelectorAll("[data-decorum-control]");
+			assert.deepEqual(
+				[...buttons].map((btn) => btn.getAttribute("data-decorum-control")),
+				["minimize", "maximize", "close"],
+			);
+
+			assert.deepEqual(mountedParts(env), [
+				["titlebar", null],
+				["controls", null],
+			]);
+			assertSettled(env);
+		});
+
+		it("puts the controls at the start of the titlebar for a left layout", async () => {
+			const env = await load(readyState, {
+				page: (document) => {
+					const bar = document.createElement("header");
+					bar.setAttribute("data-tauri-decorum-tb", "");
+					bar.appendChild(document.createElement("h1"));
+					document.body.appendChild(bar);
+				},
+				config: { style: "gnome", platform: "linux", side: "left" },
+			});
+
+			const bar = env.document.querySelector("[data-tauri-decorum-tb]");
+			assert.equal(bar.firstElementChild.localName, "decorum-controls");
+			assert.equal(bar.firstElementChild.getAttribute("data-decorum-side"), "left");
+			assertSettled(env);
+		});
+
+		it("uses the titlebar already on the page", async () => {
+			const env = await load(readyState, {
+				page: (document) => {
+					const bar = document.createElement("header");
+					bar.setAttribute("data-tauri-decorum-tb", "");
+					document.body.appendChild(bar);
+				},
+			});
+
+			const bars = env.document.querySelectorAll("[data-tauri-decorum-tb]");
+			assert.equal(bars.length, 1);
+			assert.equal(bars[0].localName, "header");
+			assert.equal(env.document.querySelectorAll("decorum-controls").length, 1);
+			assertSettled(env);
+		});
+
+		it("keeps one set of controls when injected again", async () => {
+			const env = await load(readyState);
+
+			// A page load reported twice, or set_control_state evaluating the controls again
+			env.run(titlebar());
+			env.run(controls({ controls: ["close"] }));
+			await env.settle();
+
+			assert.equal(env.document.querySelectorAll("[data-tauri-decorum-tb]").length, 1);
+
+			const sets = env.document.querySelectorAll("decorum-controls");
+			assert.equal(sets.length, 1);
+			assert.equal(sets[0].shadowRoot.querySelectorAll("[data-decorum-control]").length, 1);
+			assertSettled(env);
+		});
+
+		it("puts the controls back when the titlebar is re-rendered", async () => {
+			const env = await load(readyState);
+
+			const replacement = env.document.createElement("div");
+			replacement.setAttribute("data-tauri-decorum-tb", "");
+			env.document.querySelector("decorum-titlebar").remove();
+			env.document.body.appendChild(replacement);
+			await env.settle();
+
+			const sets = env.document.querySelectorAll("decorum-controls");
+			assert.equal(sets.length, 1);
+			assert.equal(sets[0].parentNode, replacement);
+			assertSettled(env);
+		});
+
+		it("waits for a mount point set from rust without adding a titlebar", async () => {
+			const env = await load(readyState, {
+				options: { mount: "#tabs" },
+				config: { mount: "#tabs" },
+			});
+
+			assert.equal(env.document.querySelectorAll("[data-tauri-decorum-tb]").length, 0);
+			assert.equal(env.timers.size, 1);
+
+			// Rendered by the page's framework once it starts
+			const tabs = env.document.createElement("nav");
+			tabs.setAttribute("id", "tabs");
+			env.document.body.appendChild(tabs);
+			await env.settle();
+
+			assert.equal(env.document.querySelectorAll("[data-tauri-decorum-tb]").length, 0);
+			assert.equal(env.document.querySelector("decorum-controls").parentNode, tabs);
+			assertSettled(env);
+		});
+
+		it("takes the created titlebar down when a mount point is rendered later", async () => {
+			const env = await load(readyState);
+
+			const header = env.document.createElement("header");
+			header.setAttribute("data-decorum-controls", "");
+			env.document.body.appendChild(header);
+			await env.settle();
+
+			assert.equal(env.document.querySelectorAll("[data-tauri-decorum-tb]").length, 0);
+			assert.equal(env.document.querySelector("decorum-controls").parentNode, header);
+			assertSettled(env);
+		});
+
+		it("adds nothing in headless mode", async () => {
+			const env = await load(readyState, { options: { headless: true } });
+
+			assert.equal(env.document.querySelectorAll("[data-tauri-decorum-tb]").length, 0);
+			assert.deepEqual(mountedParts(env), [["titlebar", null]]);
+			assert.equal(env.timers.size, 0);
+		});
+	});
+}
+
+describe("dragging the window", () => {
+	const startedDragging = (env) =>
+		env.invokes.filter(({ command }) => command === "startDragging").length;
+
+	it("leaves presses on the titlebar to tauri", async () => {
+		const env = await load("complete");
+
+		// Tauri 2.12 looks for the attribute on the pressed element, older versions on the target
+		const bar = env.document.querySelector("decorum-titlebar");
+		const drag = bar.shadowRoot.querySelector('[part="drag"]');
+		assert.ok(drag.hasAttribute("data-tauri-drag-region"));
+		assert.ok(bar.hasAttribute("data-tauri-drag-region"));
+
+		const press = env.press(drag);
+		assert.equal(press.path[0], drag);
+		assert.equal(press.target, bar);
+		assert.equal(startedDragging(env), 0);
+	});
+
+	it("drags from app-region: drag, but not from no-drag inside it", async () => {
+		let title, search;
+		const env = await load("complete", {
+			page: (document) => {
+				const toolbar = document.createElement("header");
+				toolbar.setAttribute("style", "-webkit-app-region: drag");
+				title = document.createElement("span");
+				search = document.createElement("input");
+				search.setAttribute("style", "app-region: no-drag");
+				toolbar.append(title, search);
+				document.body.appendChild(toolbar);
+			},
+		});
+
+		assert.ok(env.press(title).defaultPrevented);
+		assert.equal(startedDragging(env), 1);
+
+		env.press(search);
+		assert.equal(startedDragging(env), 1);
+	});
+
+	it("never drags from the window controls", async () => {
+		const env = await load("complete");
+
+		const bar = env.document.querySelector("decorum-titlebar");
+		bar.setAttribute("style", "app-region: drag");
+
+		const close = env.document
+			.querySelector("decorum-controls")
+			.shadowRoot.querySelector('[data-decorum-control="close"]');
+		env.press(close);
+		assert.equal(startedDragging(env), 0);
+	});
+
+	it("follows app-region rules in stylesheets as they change", async () => {
+		let toolbar;
+		const env = await load("complete", {
+			page: (document) => {
+				const style = document.createElement("style");
+				style.textContent = `
+					header { -webkit-app-region: drag; }
+					@media (max-width: 1px) {
+						header { -webkit-app-region: no-drag; }
+					}
+				`;
+				document.head.appendChild(style);
+
+				toolbar = document.createElement("header");
+				document.body.appendChild(toolbar);
+			},
+		});
+
+		// The media query doesn't match
+		env.press(toolbar);
+		assert.equal(startedDragging(env), 1);
+
+		const style = env.document.createElement("style");
+		style.textContent = "header { app-region: no-drag; }";
+		env.document.head.appendChild(style);
+		await env.settle();
+
+		env.press(toolbar);
+		assert.equal(startedDragging(env), 1);
+
+		style.remove();
+		await env.settle();
+
+		env.press(toolbar);
+		assert.equal(startedDragging(env), 2);
+	});
+});